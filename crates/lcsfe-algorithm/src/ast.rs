@@ -1,3 +1,5 @@
 pub mod fixpoint_system;
+pub mod lattice;
+pub mod operator_table;
 pub mod symbolic_moves;
 pub mod symbolic_moves_composed;
@@ -1,19 +1,25 @@
 use std::fmt;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+use rustc_hash::FxHashMap as HashMap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum FixType {
     Min,
     Max,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FixEq {
     pub var: String,
     pub fix_ty: FixType,
     pub exp: ExpFixEq,
+    /// The name of the basis of the lattice the variable ranges over, or
+    /// `None` for the default basis of the system
+    pub basis: Option<String>,
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum ExpFixEq {
     And(Box<ExpFixEq>, Box<ExpFixEq>),
     Or(Box<ExpFixEq>, Box<ExpFixEq>),
@@ -21,6 +27,141 @@ pub enum ExpFixEq {
     Id(String),
 }
 
+impl ExpFixEq {
+    /// The operators applied in the expression, in order of appearance and
+    /// possibly repeated.
+    pub fn operators(&self) -> Vec<&String> {
+        let mut ops = vec![];
+        self.visit(&mut |exp| {
+            if let ExpFixEq::Operator(op, _) = exp {
+                ops.push(op)
+            }
+        });
+        ops
+    }
+
+    /// The variables occurring in the expression, in order of appearance and
+    /// possibly repeated.
+    pub fn variables(&self) -> Vec<&String> {
+        let mut vars = vec![];
+        self.visit(&mut |exp| {
+            if let ExpFixEq::Id(x) = exp {
+                vars.push(x)
+            }
+        });
+        vars
+    }
+
+    /// Calls `f` on the expression and on each of its subexpressions, in
+    /// order of appearance.
+    pub fn visit<'a>(&'a self, f: &mut impl FnMut(&'a ExpFixEq)) {
+        f(self);
+        match self {
+            ExpFixEq::And(l, r) | ExpFixEq::Or(l, r) => {
+                l.visit(f);
+                r.visit(f);
+            }
+            ExpFixEq::Operator(_, args) => args.iter().for_each(|arg| arg.visit(f)),
+            ExpFixEq::Id(_) => {}
+        }
+    }
+}
+
+/// Returns the alternation depth of the system: the greatest number of blocks
+/// of consecutive equations with the same fixpoint type, among the equations
+/// of a set of mutually recursive variables. An equation whose variable does
+/// not depend on itself has no alternation, and does not count.
+pub fn alternation_depth(fix_system: &[FixEq]) -> usize {
+    let index = fix_system
+        .iter()
+        .enumerate()
+        .map(|(i, eq)| (eq.var.as_str(), i))
+        .collect::<HashMap<_, _>>();
+    let deps = fix_system
+        .iter()
+        .map(|eq| {
+            eq.exp
+                .variables()
+                .into_iter()
+                .filter_map(|x| index.get(x.as_str()).copied())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    strongly_connected_components(&deps)
+        .into_iter()
+        .filter(|scc| scc.len() > 1 || deps[scc[0]].contains(&scc[0]))
+        .map(|mut scc| {
+            scc.sort();
+            1 + scc
+                .windows(2)
+                .filter(|w| fix_system[w[0]].fix_ty != fix_system[w[1]].fix_ty)
+                .count()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Tarjan's algorithm, without recursion since systems obtained from large
+/// models have long chains of dependencies.
+fn strongly_connected_components(succ: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n = succ.len();
+    let mut index = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = vec![];
+    let mut sccs = vec![];
+    let mut counter = 0;
+
+    for root in 0..n {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        // Each frame is a node along with the position of the next successor
+        let mut frames = vec![(root, 0)];
+        index[root] = counter;
+        low[root] = counter;
+        counter += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some((v, i)) = frames.last_mut() {
+            let v = *v;
+            if let Some(&w) = succ[v].get(*i) {
+                *i += 1;
+                if index[w] == usize::MAX {
+                    index[w] = counter;
+                    low[w] = counter;
+                    counter += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    frames.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some((u, _)) = frames.last() {
+                low[*u] = low[*u].min(low[v]);
+            }
+            if low[v] == index[v] {
+                let mut scc = vec![];
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    scc.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                sccs.push(scc);
+            }
+        }
+    }
+    sccs
+}
+
 impl fmt::Display for FixType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let fix_ty = match self {
@@ -59,6 +200,28 @@ impl fmt::Display for ExpFixEq {
 
 impl fmt::Display for FixEq {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} {} {}", self.var, self.fix_ty, self.exp)
+        match &self.basis {
+            Some(basis) => write!(f, "{}: {} {} {}", self.var, basis, self.fix_ty, self.exp),
+            None => write!(f, "{} {} {}", self.var, self.fix_ty, self.exp),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::alternation_depth;
+    use crate::parse;
+
+    #[test]
+    fn alternation_depth_of_nested_fixpoints() {
+        let arity = parse::parse_fun_arity("diamond 1".to_string()).unwrap();
+        let parse = |src: &str| parse::parse_fixpoint_system(&arity, src.to_string()).unwrap();
+        assert_eq!(
+            alternation_depth(&parse("x =max y; y =min diamond(y) or x; z =max x;")),
+            2
+        );
+        assert_eq!(alternation_depth(&parse("x =max diamond(y); y =min x;")), 2);
+        assert_eq!(alternation_depth(&parse("x =max diamond(y); y =max x;")), 1);
+        assert_eq!(alternation_depth(&parse("x =max y; y =min diamond(y);")), 1);
     }
 }
@@ -0,0 +1,402 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use rustc_hash::FxHashMap as HashMap;
+
+use super::symbolic_moves::SymbolicExistsMoves;
+
+/// A finite lattice, given by its elements and its order relation. The basis
+/// of the lattice is the set of its join-irreducible elements: every element
+/// is the join of the join-irreducible elements below it.
+#[derive(Debug, Clone)]
+pub struct Lattice {
+    elements: Vec<String>,
+    /// `leq[x][y]` holds whenever `x` is below `y`
+    leq: Vec<Vec<bool>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LatticeError {
+    details: String,
+}
+
+impl LatticeError {
+    fn new(details: String) -> LatticeError {
+        LatticeError { details }
+    }
+}
+
+impl std::error::Error for LatticeError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+impl fmt::Display for LatticeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid lattice: {}", self.details)
+    }
+}
+
+impl Lattice {
+    /// Builds the lattice from its elements and a set of pairs `(x, y)`
+    /// such that `x` is below `y`, for example the edges of the Hasse
+    /// diagram: the order is their reflexive and transitive closure. Fails if
+    /// the closure is not antisymmetric, or if some elements have no join,
+    /// or if there is no bottom element.
+    pub fn new(elements: Vec<String>, below: &[(String, String)]) -> Result<Lattice, LatticeError> {
+        let index = elements
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (x.as_str(), i))
+            .collect::<HashMap<_, _>>();
+        let n = elements.len();
+
+        let mut leq = vec![vec![false; n]; n];
+        (0..n).for_each(|x| leq[x][x] = true);
+        for (x, y) in below {
+            match (index.get(x.as_str()), index.get(y.as_str())) {
+                (Some(x), Some(y)) => leq[*x][*y] = true,
+                _ => {
+                    return Err(LatticeError::new(format!(
+                        "the pair {} < {} refers to an unknown element",
+                        x, y
+                    )))
+                }
+            }
+        }
+        for k in 0..n {
+            for x in 0..n {
+                for y in 0..n {
+                    leq[x][y] = leq[x][y] || (leq[x][k] && leq[k][y]);
+                }
+            }
+        }
+
+        let lattice = Lattice { elements, leq };
+        for x in 0..n {
+            for y in (x + 1)..n {
+                if lattice.leq[x][y] && lattice.leq[y][x] {
+                    return Err(LatticeError::new(format!(
+                        "{} and {} are below each other",
+                        lattice.elements[x], lattice.elements[y]
+                    )));
+                }
+                if lattice.join(x, y).is_none() {
+                    return Err(LatticeError::new(format!(
+                        "{} and {} have no join",
+                        lattice.elements[x], lattice.elements[y]
+                    )));
+                }
+            }
+        }
+        if lattice.bottom().is_none() {
+            return Err(LatticeError::new("there is no bottom element".to_string()));
+        }
+
+        Ok(lattice)
+    }
+
+    pub fn elements(&self) -> &[String] {
+        &self.elements
+    }
+
+    pub fn leq(&self, x: usize, y: usize) -> bool {
+        self.leq[x][y]
+    }
+
+    pub fn index(&self, x: &str) -> Option<usize> {
+        self.elements.iter().position(|y| y == x)
+    }
+
+    /// Returns the least upper bound of `x` and `y`, if any.
+    pub fn join(&self, x: usize, y: usize) -> Option<usize> {
+        let upper = (0..self.elements.len())
+            .filter(|z| self.leq[x][*z] && self.leq[y][*z])
+            .collect::<Vec<_>>();
+        upper
+            .iter()
+            .copied()
+            .find(|z| upper.iter().all(|w| self.leq[*z][*w]))
+    }
+
+    /// Returns the join of the elements `xs`, if they are elements of the
+    /// lattice. The join of no element is the bottom.
+    pub fn join_all(&self, xs: &[String]) -> Option<String> {
+        let join = xs
+            .iter()
+            .try_fold(self.bottom()?, |join, x| self.join(join, self.index(x)?))?;
+        Some(self.elements[join].to_owned())
+    }
+
+    fn bottom(&self) -> Option<usize> {
+        (0..self.elements.len()).find(|x| (0..self.elements.len()).all(|y| self.leq[*x][y]))
+    }
+
+    /// Returns the join-irreducible elements, that is the elements which
+    /// cover exactly one element. The bottom covers no element, hence it is
+    /// never join-irreducible.
+    pub fn join_irreducibles(&self) -> Vec<usize> {
+        (0..self.elements.len())
+            .filter(|x| self.lower_covers(*x).len() == 1)
+            .collect()
+    }
+
+    /// The basis of the lattice, i.e. the names of its join-irreducible
+    /// elements.
+    pub fn basis(&self) -> Vec<String> {
+        self.join_irreducibles()
+            .into_iter()
+            .map(|x| self.elements[x].to_owned())
+            .collect()
+    }
+
+    /// Returns the basis elements below the element `x`, whose join is `x`,
+    /// if `x` is an element of the lattice.
+    pub fn basis_below(&self, x: &str) -> Option<Vec<String>> {
+        let x = self.index(x)?;
+        Some(
+            self.join_irreducibles()
+                .into_iter()
+                .filter(|b| self.leq[*b][x])
+                .map(|b| self.elements[b].to_owned())
+                .collect(),
+        )
+    }
+
+    fn lower_covers(&self, x: usize) -> Vec<usize> {
+        let below = (0..self.elements.len())
+            .filter(|y| *y != x && self.leq[*y][x])
+            .collect::<Vec<_>>();
+        below
+            .iter()
+            .copied()
+            .filter(|y| !below.iter().any(|z| z != y && self.leq[*y][*z]))
+            .collect()
+    }
+
+    /// Checks that the symbolic exists-moves respect the order: whenever the
+    /// basis element `b'` is below `b`, every move for `b` and an operator
+    /// must also be a move for `b'` and the same operator, once the sets of
+    /// the move are closed downwards.
+    pub fn validate_moves(&self, moves: &SymbolicExistsMoves) -> Result<(), LatticeError> {
+        self.validate(moves, None)
+    }
+
+    /// As `validate_moves`, when the lattice is the basis of some of the
+    /// equations only: `args` has the operators applied in those equations,
+    /// and for each argument whether it ranges over the lattice. The atoms of
+    /// the other arguments, e.g. over another basis, are not closed.
+    pub fn validate_moves_over(
+        &self,
+        moves: &SymbolicExistsMoves,
+        args: &HashMap<String, Vec<bool>>,
+    ) -> Result<(), LatticeError> {
+        self.validate(moves, Some(args))
+    }
+
+    fn validate(
+        &self,
+        moves: &SymbolicExistsMoves,
+        args: Option<&HashMap<String, Vec<bool>>>,
+    ) -> Result<(), LatticeError> {
+        let basis = self.join_irreducibles();
+        let mut errors = vec![];
+        let mut ops = moves.fun_map.keys().collect::<Vec<_>>();
+        ops.sort();
+        for op in ops {
+            let over = match args {
+                Some(args) => match args.get(op) {
+                    Some(over) => Some(over.as_slice()),
+                    None => continue,
+                },
+                None => None,
+            };
+            for b in &basis {
+                for bp in &basis {
+                    if b == bp || !self.leq[*bp][*b] {
+                        continue;
+                    }
+                    let (b, bp) = (&self.elements[*b], &self.elements[*bp]);
+                    for clause in moves.get_formula(b, op).clauses() {
+                        let closure = match self.down_closure(&clause, over) {
+                            Ok(closure) => closure,
+                            Err(x) => {
+                                errors.push(format!(
+                                    "the move {} for {} and {} refers to {}, which is not an \
+                                     element of the lattice",
+                                    show_clause(&clause),
+                                    b,
+                                    op,
+                                    x
+                                ));
+                                break;
+                            }
+                        };
+                        if !moves.get_formula(bp, op).eval(&closure) {
+                            errors.push(format!(
+                                "the move {} for {} and {} is not a move for {}, which is below",
+                                show_clause(&clause),
+                                b,
+                                op,
+                                bp
+                            ));
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            errors.sort();
+            errors.dedup();
+            Err(LatticeError::new(errors.join("\n- ")))
+        }
+    }
+
+    /// Adds to a set of basis elements, for each argument which ranges over
+    /// the lattice, i.e. each one if `over` is missing, the basis elements
+    /// below them, which does not change their join. Fails with an element
+    /// of such an argument which is not in the lattice.
+    fn down_closure(
+        &self,
+        clause: &BTreeSet<(String, usize)>,
+        over: Option<&[bool]>,
+    ) -> Result<BTreeSet<(String, usize)>, String> {
+        let basis = self.join_irreducibles();
+        let mut closure = BTreeSet::new();
+        for (x, i) in clause {
+            if over.is_some_and(|over| !over.get(*i).copied().unwrap_or(false)) {
+                closure.insert((x.to_owned(), *i));
+                continue;
+            }
+            let x = self.index(x).ok_or_else(|| x.to_owned())?;
+            closure.extend(
+                basis
+                    .iter()
+                    .filter(|bp| self.leq[**bp][x])
+                    .map(|bp| (self.elements[*bp].to_owned(), *i)),
+            );
+        }
+        Ok(closure)
+    }
+}
+
+fn show_clause(clause: &BTreeSet<(String, usize)>) -> String {
+    format!(
+        "{{{}}}",
+        clause
+            .iter()
+            .map(|(x, i)| format!("[{}, {}]", x, i + 1))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lattice;
+    use crate::algorithm::{LocalAlgorithm, Player};
+    use crate::ast::symbolic_moves_composed::SymbolicExistsMoves;
+    use crate::fixtures;
+    use crate::parse;
+    use crate::powerset_game::query::Query;
+
+    /// In `tests/example_chain` the lattice is the chain `0 < 1 < 2 < 3`,
+    /// whose basis is `1, 2, 3`, and the least fixpoint of
+    /// `x = succ(x) or one()` is the top element.
+    #[test]
+    fn chain() {
+        let src = |f| fixtures::src("example_chain", f);
+        let arity = parse::parse_fun_arity(src("arity")).unwrap();
+        let fix_system = parse::parse_fixpoint_system(&arity, src("fix_system")).unwrap();
+        let lattice = parse::parse_lattice(src("lattice")).unwrap();
+        let basis = lattice.basis();
+        assert_eq!(basis, vec!["1", "2", "3"]);
+        let moves = parse::parse_symbolic_system(&arity, &basis, src("symbolic")).unwrap();
+        assert!(lattice.validate_moves(&moves).is_ok());
+
+        let moves = SymbolicExistsMoves::compose(&fix_system, &moves, &basis).unwrap();
+        let algo = LocalAlgorithm {
+            fix_system: &fix_system,
+            symbolic_moves: &moves,
+            up_to: None,
+        };
+        for b in &basis {
+            assert_eq!(algo.local_check(b.to_owned(), 0).unwrap(), Player::Eve);
+        }
+
+        // A move for 3 which is not a move for 2
+        let moves = parse::parse_symbolic_system(
+            &arity,
+            &basis,
+            "phi(2)(succ) = [2, 1]; phi(3)(succ) = [1, 1];".to_string(),
+        )
+        .unwrap();
+        assert!(lattice.validate_moves(&moves).is_err());
+    }
+
+    /// Over M3, i.e. `bot < a, b, c < top`, the solution of `x = k()` with
+    /// `phi(c)(k) = true` is `c`, which is below the join of `a` and `b`
+    /// although it is not below either of them.
+    #[test]
+    fn join_of_elements() {
+        let arity = parse::parse_fun_arity("k 0".to_string()).unwrap();
+        let fix_system = parse::parse_fixpoint_system(&arity, "x =max k();".to_string()).unwrap();
+        let lattice =
+            parse::parse_lattice("bot < a < top; bot < b < top; bot < c < top;".to_string())
+                .unwrap();
+        let basis = lattice.basis();
+        let moves = parse::parse_symbolic_system(
+            &arity,
+            &basis,
+            "phi(a)(k) = false; phi(b)(k) = false; phi(c)(k) = true;".to_string(),
+        )
+        .unwrap();
+        let moves = SymbolicExistsMoves::compose(&fix_system, &moves, &basis).unwrap();
+        let algo = LocalAlgorithm {
+            fix_system: &fix_system,
+            symbolic_moves: &moves,
+            up_to: None,
+        };
+
+        let join = lattice
+            .join_all(&["a".to_string(), "b".to_string()])
+            .unwrap();
+        assert_eq!(join, "top");
+        assert_eq!(lattice.join_all(&[]).unwrap(), "bot");
+        assert!(lattice.join_all(&["d".to_string()]).is_none());
+        for x in [join.as_str(), "top"] {
+            let elements = lattice.basis_below(x).unwrap();
+            assert_eq!(elements, vec!["a", "b", "c"]);
+            assert_eq!(
+                algo.check_query(&Query::Above(elements, 0)).unwrap(),
+                Player::Eve
+            );
+        }
+        // The basis elements below `a` or `b` are not closed downwards
+        assert_eq!(
+            algo.check_query(&Query::Above(vec!["a".to_string(), "b".to_string()], 0))
+                .unwrap(),
+            Player::Adam
+        );
+    }
+
+    #[test]
+    fn not_a_lattice() {
+        // Two maximal elements without a join
+        assert!(Lattice::new(
+            vec!["0".to_string(), "a".to_string(), "b".to_string()],
+            &[
+                ("0".to_string(), "a".to_string()),
+                ("0".to_string(), "b".to_string())
+            ],
+        )
+        .is_err());
+        // A cycle
+        assert!(parse::parse_lattice("0 < a < b < a;".to_string()).is_err());
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use rustc_hash::FxHashMap as HashMap;
+
+use super::symbolic_moves::{LogicFormula, SymbolicExistsMoves};
+
+/// A monotone function over the powerset lattice of the basis, given as a
+/// table. Each entry maps a tuple of sets of basis elements, one for each
+/// argument, to a set of basis elements. The function is the least monotone
+/// function consistent with the table, that is `f(X_1, ..., X_n)` is the
+/// union of the outputs of the entries whose inputs are included in
+/// `X_1, ..., X_n`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorTable {
+    pub name: String,
+    pub arity: usize,
+    pub entries: Vec<(Vec<BTreeSet<String>>, BTreeSet<String>)>,
+}
+
+impl OperatorTable {
+    /// Applies the function to the tuple of sets `args`.
+    pub fn apply(&self, args: &[BTreeSet<String>]) -> BTreeSet<String> {
+        self.entries
+            .iter()
+            .filter(|(input, _)| input.iter().zip(args).all(|(x, y)| x.is_subset(y)))
+            .flat_map(|(_, output)| output.iter().cloned())
+            .collect()
+    }
+
+    /// Returns the minimal inputs whose output contains the basis element
+    /// `b`, keeping only the first of equal inputs.
+    pub fn minimal_inputs(&self, b: &str) -> Vec<&Vec<BTreeSet<String>>> {
+        let inputs = self
+            .entries
+            .iter()
+            .filter(|(_, output)| output.contains(b))
+            .map(|(input, _)| input)
+            .collect::<Vec<_>>();
+        let included = |x: &Vec<BTreeSet<String>>, y: &Vec<BTreeSet<String>>| {
+            x.iter().zip(y).all(|(x_i, y_i)| x_i.is_subset(y_i))
+        };
+
+        // An input is kept if no other input is strictly smaller, or the
+        // first of equal inputs
+        inputs
+            .iter()
+            .enumerate()
+            .filter(|(n, x)| {
+                !inputs
+                    .iter()
+                    .enumerate()
+                    .any(|(m, y)| included(y, x) && (!included(x, y) || m < *n))
+            })
+            .map(|(_, x)| *x)
+            .collect()
+    }
+
+    /// Returns the minimal symbolic exists-move for the basis element `b`:
+    /// the disjunction of the minimal inputs whose output contains `b`, where
+    /// each input is the conjunction of its elements.
+    pub fn symbolic_move(&self, b: &str) -> LogicFormula {
+        let mut minimal = self
+            .minimal_inputs(b)
+            .into_iter()
+            .map(|x| {
+                let mut conj = x
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, x_i)| {
+                        x_i.iter()
+                            .map(move |b| LogicFormula::BasisElem(b.to_owned(), i))
+                    })
+                    .collect::<Vec<_>>();
+                match conj.len() {
+                    0 => LogicFormula::True,
+                    1 => conj.pop().unwrap(),
+                    _ => LogicFormula::Conj(conj),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        match minimal.len() {
+            0 => LogicFormula::False,
+            1 => minimal.pop().unwrap(),
+            _ => LogicFormula::Disj(minimal),
+        }
+    }
+}
+
+/// Computes the symbolic exists-moves of every basis element and operator
+/// in `arity`, from the tables of the operators. An operator without a table
+/// is the constant function which returns the empty set.
+pub fn derive_symbolic_moves(
+    arity: &[(String, usize)],
+    basis: &[String],
+    tables: &[OperatorTable],
+) -> SymbolicExistsMoves {
+    let basis_map = basis
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.to_owned(), i))
+        .collect::<HashMap<String, usize>>();
+    let fun_map = arity
+        .iter()
+        .enumerate()
+        .map(|(i, (f, _))| (f.to_owned(), i))
+        .collect::<HashMap<String, usize>>();
+
+    let mut formulas = vec![LogicFormula::False; basis.len() * arity.len()];
+    for table in tables {
+        for (b, b_i) in &basis_map {
+            formulas[fun_map[&table.name] * basis.len() + b_i] = table.symbolic_move(b);
+        }
+    }
+
+    SymbolicExistsMoves {
+        basis_map,
+        fun_map,
+        formulas,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MovesError {
+    details: String,
+}
+
+impl MovesError {
+    fn new(details: String) -> MovesError {
+        MovesError { details }
+    }
+}
+
+impl std::error::Error for MovesError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+impl fmt::Display for MovesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid symbolic exists-moves:\n- {}", self.details)
+    }
+}
+
+/// Checks that the symbolic exists-moves are correct with respect to the
+/// tables of the operators, which are the least monotone functions over the
+/// powerset of the basis described in `OperatorTable`. For each basis
+/// element `b` and operator `f`, the formula `phi(b)(f)` must be:
+/// - sound, every model of the formula is a tuple of sets whose image
+///   through `f` contains `b`; by monotonicity it is enough to check the
+///   minimal models;
+/// - complete, every minimal tuple of sets whose image contains `b` is a
+///   model of the formula.
+///
+/// An operator without a table is the constant function which returns the
+/// empty set. All the offending basis elements and operators are reported.
+pub fn validate_symbolic_moves(
+    arity: &[(String, usize)],
+    basis: &[String],
+    tables: &[OperatorTable],
+    moves: &SymbolicExistsMoves,
+) -> Result<(), MovesError> {
+    let show = |args: &[BTreeSet<String>]| {
+        args.iter()
+            .map(|x| format!("{{{}}}", x.iter().cloned().collect::<Vec<_>>().join(", ")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut errors = vec![];
+    for (f, n) in arity.iter().filter(|(f, _)| f != "and" && f != "or") {
+        let table = tables
+            .iter()
+            .find(|table| &table.name == f)
+            .cloned()
+            .unwrap_or_else(|| OperatorTable {
+                name: f.to_owned(),
+                arity: *n,
+                entries: vec![],
+            });
+        for b in basis {
+            let formula = moves.get_formula(b, f);
+            for clause in formula.clauses() {
+                if let Some((bp, i)) = clause.iter().find(|(_, i)| i >= n) {
+                    errors.push(format!(
+                        "phi({})({}) refers to [{}, {}], but {} has arity {}",
+                        b,
+                        f,
+                        bp,
+                        i + 1,
+                        f,
+                        n
+                    ));
+                    continue;
+                }
+                let mut args = vec![BTreeSet::new(); *n];
+                for (bp, i) in clause {
+                    args[i].insert(bp);
+                }
+                if !table.apply(&args).contains(b) {
+                    errors.push(format!(
+                        "phi({})({}) is not sound: {} is not in {}({})",
+                        b,
+                        f,
+                        b,
+                        f,
+                        show(&args)
+                    ));
+                }
+            }
+            for input in table.minimal_inputs(b) {
+                let model = input
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, x)| x.iter().map(move |bp| (bp.to_owned(), i)))
+                    .collect();
+                if !formula.eval(&model) {
+                    errors.push(format!(
+                        "phi({})({}) is not complete: it does not allow the minimal move ({})",
+                        b,
+                        f,
+                        show(input)
+                    ));
+                }
+            }
+        }
+    }
+
+    errors.dedup();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(MovesError::new(errors.join("\n- ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::LocalAlgorithm;
+    use crate::ast::symbolic_moves_composed::SymbolicExistsMoves;
+    use crate::fixtures::{self, Example};
+    use crate::parse;
+
+    /// The symbolic exists-moves derived from `tests/example_01/operators`
+    /// must solve the system as the ones written by hand in
+    /// `tests/example_01/symbolic`, which are correct for the tables.
+    #[test]
+    fn derive_moves_example_01() {
+        let src = |f| fixtures::src("example_01", f);
+        let example = Example::load("example_01");
+        let Example {
+            arity,
+            fix_system,
+            basis,
+            ..
+        } = &example;
+        let by_hand = parse::parse_symbolic_system(arity, basis, src("symbolic")).unwrap();
+        let tables = parse::parse_operators(arity, basis, src("operators")).unwrap();
+        let derived = super::derive_symbolic_moves(arity, basis, &tables);
+        assert!(super::validate_symbolic_moves(arity, basis, &tables, &by_hand).is_ok());
+
+        // Swapping the moves of box and diamond for {a} breaks both
+        // soundness and completeness
+        let wrong = src("symbolic")
+            .replace("[{a}, 1] and [{b}, 1] and [{c}, 1]", "X")
+            .replace(
+                "[{a}, 1] or [{b}, 1] or [{c}, 1]",
+                "[{a}, 1] and [{b}, 1] and [{c}, 1]",
+            )
+            .replace("X", "[{a}, 1] or [{b}, 1] or [{c}, 1]");
+        let wrong = parse::parse_symbolic_system(arity, basis, wrong).unwrap();
+        let errors = super::validate_symbolic_moves(arity, basis, &tables, &wrong)
+            .unwrap_err()
+            .to_string();
+        assert!(errors.contains("phi({a})(box) is not sound"));
+        assert!(errors.contains("phi({a})(diamond) is not complete"));
+
+        let derived = SymbolicExistsMoves::compose(fix_system, &derived, basis).unwrap();
+        for b in basis {
+            for i in 0..fix_system.len() {
+                let solve = |moves| {
+                    LocalAlgorithm {
+                        symbolic_moves: moves,
+                        ..example.algorithm()
+                    }
+                    .local_check(b.to_owned(), i)
+                    .unwrap()
+                };
+                assert_eq!(solve(&example.moves), solve(&derived), "{}, {}", b, i + 1);
+            }
+        }
+    }
+}
@@ -1,31 +1,137 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+use std::hash::Hash;
+
 use rustc_hash::FxHashMap as HashMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
-pub struct SymbolicExistsMoves {
-    pub basis_map: HashMap<String, usize>,
+/// The symbolic exists-moves for each basis element and function, where
+/// basis elements have type `B`: strings when parsed from a file, though a
+/// library user may use its own type, e.g. the states of a transition
+/// system.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "B: Serialize",
+    deserialize = "B: Deserialize<'de> + Hash + Eq"
+))]
+pub struct SymbolicExistsMoves<B = String> {
+    pub basis_map: HashMap<B, usize>,
     pub fun_map: HashMap<String, usize>,
-    pub formulas: Vec<LogicFormula>,
+    pub formulas: Vec<LogicFormula<B>>,
 }
 
 impl SymbolicExistsMoves {
-    pub fn get_formula(&self, basis_elem: &String, fun: &String) -> &LogicFormula {
-        &self.formulas[self.fun_map.get(fun).unwrap() * self.basis_map.len()
-            + self.basis_map.get(basis_elem).unwrap()]
+    /// Builds the table of the moves listed as triples `(b, f, formula)`,
+    /// for the operators in `arity` and the elements of `basis`. The moves
+    /// which are not listed are `false`, and a move replaces the previous ones
+    /// for the same basis element and operator.
+    pub fn from_list(
+        arity: &[(String, usize)],
+        basis: &[String],
+        moves: Vec<(String, String, LogicFormula)>,
+    ) -> SymbolicExistsMoves {
+        let basis_map = basis
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (b.to_owned(), i))
+            .collect::<HashMap<String, usize>>();
+        let fun_map = arity
+            .iter()
+            .enumerate()
+            .map(|(i, (f, _))| (f.to_owned(), i))
+            .collect::<HashMap<String, usize>>();
+        let mut formulas = vec![LogicFormula::False; basis_map.len() * fun_map.len()];
+        moves
+            .into_iter()
+            .for_each(|(b, f, l)| formulas[fun_map[&f] * basis_map.len() + basis_map[&b]] = l);
+        SymbolicExistsMoves {
+            basis_map,
+            fun_map,
+            formulas,
+        }
+    }
+}
+
+impl<B: Hash + Eq + Clone> SymbolicExistsMoves<B> {
+    /// The move for the basis element and the operator, which is `false` if
+    /// either is not in the table, as the moves which are not listed.
+    pub fn get_formula(&self, basis_elem: &B, fun: &str) -> Cow<'_, LogicFormula<B>> {
+        match (self.fun_map.get(fun), self.basis_map.get(basis_elem)) {
+            (Some(f), Some(b)) => Cow::Borrowed(&self.formulas[f * self.basis_map.len() + b]),
+            _ => Cow::Owned(LogicFormula::False),
+        }
+    }
+}
+
+/// A source of symbolic exists-moves, which are computed on demand for a
+/// basis element and an operator. The moves of an operator with arity `n`
+/// may only refer to the arguments `[b, 1]` to `[b, n]`, with indices
+/// starting from 0 in `LogicFormula::BasisElem`.
+///
+/// Moves are either read from a table, i.e. `SymbolicExistsMoves`, or
+/// computed by a closure `Fn(&B, &str) -> LogicFormula<B>`, for example from
+/// a model of a program, without materialising the whole table.
+pub trait MoveProvider<B: Clone = String> {
+    fn get_move(&self, b: &B, op: &str) -> Cow<'_, LogicFormula<B>>;
+}
+
+impl<B: Hash + Eq + Clone> MoveProvider<B> for SymbolicExistsMoves<B> {
+    fn get_move(&self, b: &B, op: &str) -> Cow<'_, LogicFormula<B>> {
+        self.get_formula(b, op)
     }
 }
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub enum LogicFormula {
-    BasisElem(String, usize),
+
+impl<B: Clone, F: Fn(&B, &str) -> LogicFormula<B>> MoveProvider<B> for F {
+    fn get_move(&self, b: &B, op: &str) -> Cow<'_, LogicFormula<B>> {
+        Cow::Owned(self(b, op))
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum LogicFormula<B = String> {
+    BasisElem(B, usize),
     True,
     False,
-    Conj(Vec<LogicFormula>),
-    Disj(Vec<LogicFormula>),
+    Conj(Vec<LogicFormula<B>>),
+    Disj(Vec<LogicFormula<B>>),
 }
 
-impl std::fmt::Display for LogicFormula {
+impl<B: Ord + Clone> LogicFormula<B> {
+    /// Returns the models of the formula which are minimal with respect to
+    /// the positive atoms, i.e. its disjunctive normal form, possibly with
+    /// redundant clauses.
+    pub fn clauses(&self) -> Vec<BTreeSet<(B, usize)>> {
+        match self {
+            LogicFormula::BasisElem(b, i) => vec![BTreeSet::from([(b.clone(), *i)])],
+            LogicFormula::True => vec![BTreeSet::new()],
+            LogicFormula::False => vec![],
+            LogicFormula::Disj(xs) => xs.iter().flat_map(LogicFormula::clauses).collect(),
+            LogicFormula::Conj(xs) => xs.iter().fold(vec![BTreeSet::new()], |acc, x| {
+                let clauses = x.clauses();
+                acc.iter()
+                    .flat_map(|c| clauses.iter().map(|d| c.union(d).cloned().collect()))
+                    .collect()
+            }),
+        }
+    }
+
+    /// Evaluates the formula, where the true atoms are those in `model`.
+    pub fn eval(&self, model: &BTreeSet<(B, usize)>) -> bool {
+        match self {
+            LogicFormula::BasisElem(b, i) => model.contains(&(b.clone(), *i)),
+            LogicFormula::True => true,
+            LogicFormula::False => false,
+            LogicFormula::Disj(xs) => xs.iter().any(|x| x.eval(model)),
+            LogicFormula::Conj(xs) => xs.iter().all(|x| x.eval(model)),
+        }
+    }
+}
+
+impl<B: std::fmt::Display> std::fmt::Display for LogicFormula<B> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let to_vec_of_str =
-            |xs: &Vec<LogicFormula>| xs.iter().map(|x| format!("{}", x)).collect::<Vec<String>>();
+        let to_vec_of_str = |xs: &Vec<LogicFormula<B>>| {
+            xs.iter().map(|x| format!("{}", x)).collect::<Vec<String>>()
+        };
 
         let formula = match self {
             LogicFormula::BasisElem(b, i) => format!("[{}, {}]", b, i),
@@ -1,33 +1,115 @@
-use std::fmt::Display;
-use std::{ops::Deref, rc::Rc};
+use std::fmt::{Debug, Display};
+use std::{borrow::Borrow, hash::Hash, ops::Deref, rc::Rc};
 
-use bimap::BiMap;
+use rustc_hash::FxHashMap as HashMap;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 use super::fixpoint_system::ExpFixEq;
-use super::symbolic_moves::LogicFormula;
-use super::{fixpoint_system::FixEq, symbolic_moves::SymbolicExistsMoves as NotComposedMoves};
+use super::fixpoint_system::FixEq;
+use super::symbolic_moves::{LogicFormula, MoveProvider};
 
-pub struct SymbolicExistsMoves {
+/// The composed symbolic exists-moves. Basis elements are identified by dense
+/// indices: `basis` is the table from indices to the elements of the bases of
+/// all the equations, which have type `B`, and `basis_index` is its inverse.
+/// Each equation has its own basis, `eq_basis`, which is a sorted set of
+/// indices, and the moves of the positions `(b, i)` of the equation `i` start
+/// from `offset[i]`. The formulas are shared, thus a clone is shallow.
+#[derive(Clone)]
+pub struct SymbolicExistsMoves<B = String> {
     symbolic_moves: Vec<Rc<Node<FormulaOperator>>>,
-    basis_map: BiMap<usize, String>,
-    m: usize,
+    basis: Vec<B>,
+    basis_index: HashMap<B, usize>,
+    eq_basis: Vec<Vec<usize>>,
+    offset: Vec<usize>,
 
     basis_elem_node: Vec<Rc<Node<FormulaOperator>>>,
     true_node: Rc<Node<FormulaOperator>>,
     false_node: Rc<Node<FormulaOperator>>,
 }
 
-impl SymbolicExistsMoves {
+#[derive(Debug, Clone)]
+pub struct ComposeError {
+    details: String,
+}
+
+impl ComposeError {
+    fn new(details: String) -> ComposeError {
+        ComposeError { details }
+    }
+}
+
+impl std::error::Error for ComposeError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+impl Display for ComposeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Cannot compose the symbolic exists-moves: {}",
+            self.details
+        )
+    }
+}
+
+impl<B: Hash + Eq + Clone + Debug> SymbolicExistsMoves<B> {
+    /// Composes the symbolic exists-moves, where every equation has the same
+    /// basis.
     pub fn compose(
         equations: &[FixEq],
-        moves: &NotComposedMoves,
-        basis: &[String],
-    ) -> SymbolicExistsMoves {
-        let mut basis_elem_nodes = Vec::with_capacity(basis.len() * equations.len());
-        for i in 0..equations.len() {
-            for b in 0..basis.len() {
+        moves: &dyn MoveProvider<B>,
+        basis: &[B],
+    ) -> Result<SymbolicExistsMoves<B>, ComposeError> {
+        Self::compose_with_bases(equations, moves, &vec![basis.to_vec(); equations.len()])
+    }
+
+    /// Composes the symbolic exists-moves, where `bases[i]` is the basis of
+    /// the lattice of the equation `i`. Equations may range over different
+    /// lattices, e.g. sets of states and booleans: whenever a move of an
+    /// operator leads to an atom `[b, j]` whose argument `j` is a variable,
+    /// `b` must belong to the basis of the equation of that variable. The
+    /// same element may belong to several bases, in which case its moves
+    /// are shared.
+    pub fn compose_with_bases(
+        equations: &[FixEq],
+        moves: &dyn MoveProvider<B>,
+        bases: &[Vec<B>],
+    ) -> Result<SymbolicExistsMoves<B>, ComposeError> {
+        if bases.len() != equations.len() {
+            return Err(ComposeError::new(format!(
+                "{} bases are given for {} equations",
+                bases.len(),
+                equations.len()
+            )));
+        }
+
+        let mut basis = vec![];
+        let mut basis_index: HashMap<B, usize> = HashMap::default();
+        let mut eq_basis = Vec::with_capacity(equations.len());
+        for eq in bases {
+            let mut indices = eq
+                .iter()
+                .map(|b| {
+                    *basis_index.entry(b.clone()).or_insert_with(|| {
+                        basis.push(b.clone());
+                        basis.len() - 1
+                    })
+                })
+                .collect::<Vec<_>>();
+            indices.sort_unstable();
+            indices.dedup();
+            eq_basis.push(indices);
+        }
+
+        let mut offset = Vec::with_capacity(equations.len());
+        let mut basis_elem_nodes = vec![];
+        for (i, eq) in eq_basis.iter().enumerate() {
+            offset.push(basis_elem_nodes.len());
+            for b in eq {
                 basis_elem_nodes.push(Rc::new(Node {
-                    val: FormulaOperator::Atom(BasisElem { b, i }),
+                    val: FormulaOperator::Atom(BasisElem { b: *b, i }),
                     children: vec![],
                 }))
             }
@@ -35,8 +117,10 @@ impl SymbolicExistsMoves {
 
         let mut symbolic_exists_moves = SymbolicExistsMoves {
             symbolic_moves: vec![],
-            m: equations.len(),
-            basis_map: basis.iter().cloned().enumerate().collect::<BiMap<_, _>>(),
+            basis,
+            basis_index,
+            eq_basis,
+            offset,
             basis_elem_node: basis_elem_nodes,
             true_node: Rc::new(Node {
                 val: FormulaOperator::And,
@@ -49,35 +133,17 @@ impl SymbolicExistsMoves {
         };
 
         let mut symbolic_moves_composed: Vec<Rc<Node<FormulaOperator>>> =
-            Vec::with_capacity(equations.len() * basis.len());
+            Vec::with_capacity(symbolic_exists_moves.basis_elem_node.len());
 
-        for i in 0..equations.len() {
-            for b_i in 0..basis.len() {
-                let n =
-                    symbolic_exists_moves.compose_moves(equations, &equations[i].exp, moves, b_i);
+        for (i, eq) in equations.iter().enumerate() {
+            for b_i in &symbolic_exists_moves.eq_basis[i] {
+                let n = symbolic_exists_moves.compose_moves(equations, &eq.exp, moves, *b_i)?;
                 symbolic_moves_composed.push(symbolic_exists_moves.simplify(n));
             }
         }
 
         symbolic_exists_moves.symbolic_moves = symbolic_moves_composed;
-        symbolic_exists_moves
-    }
-
-    #[inline(always)]
-    pub fn get_basis_usize(&self, b: &str) -> usize {
-        *self.basis_map.get_by_right(b).unwrap()
-    }
-
-    pub fn get_true_atom(&self) -> Rc<Node<FormulaOperator>> {
-        self.true_node.clone()
-    }
-
-    pub fn get_false_atom(&self) -> Rc<Node<FormulaOperator>> {
-        self.false_node.clone()
-    }
-
-    pub fn get_basis_elem_node(&self, b: usize, i: usize) -> Rc<Node<FormulaOperator>> {
-        self.basis_elem_node[i * self.basis_map.len() + b].clone()
+        Ok(symbolic_exists_moves)
     }
 
     #[inline]
@@ -85,9 +151,9 @@ impl SymbolicExistsMoves {
         &self,
         equations: &[FixEq],
         sub_exp: &ExpFixEq,
-        moves: &NotComposedMoves,
+        moves: &dyn MoveProvider<B>,
         b_i: usize,
-    ) -> Rc<Node<FormulaOperator>> {
+    ) -> Result<Rc<Node<FormulaOperator>>, ComposeError> {
         match sub_exp {
             i @ ExpFixEq::And(_, _) | i @ ExpFixEq::Or(_, _) => {
                 let n = Node {
@@ -97,51 +163,59 @@ impl SymbolicExistsMoves {
                         FormulaOperator::Or
                     },
                     children: vec![
-                        self.subst(
-                            equations,
-                            i,
-                            moves,
-                            &Rc::new(Node {
-                                val: FormulaOperator::Atom(BasisElem { b: b_i, i: 0 }),
-                                children: vec![],
-                            }),
-                        ),
-                        self.subst(
-                            equations,
-                            i,
-                            moves,
-                            &Rc::new(Node {
-                                val: FormulaOperator::Atom(BasisElem { b: b_i, i: 1 }),
-                                children: vec![],
-                            }),
-                        ),
+                        self.subst(equations, i, moves, &Self::arg_node(b_i, 0))?,
+                        self.subst(equations, i, moves, &Self::arg_node(b_i, 1))?,
                     ],
                 };
-                Rc::new(n)
+                Ok(Rc::new(n))
             }
 
             i @ ExpFixEq::Operator(op, _) => {
-                let n = Rc::new(self.logic_formula_to_tree(
-                    moves.get_formula(self.basis_map.get_by_left(&b_i).unwrap(), op),
-                ));
+                let n = self.logic_formula_to_tree(&moves.get_move(&self.basis[b_i], op))?;
                 self.subst(equations, i, moves, &n)
             }
 
-            ExpFixEq::Id(var) => self.get_basis_elem_node(b_i, Self::projection(equations, var)),
+            ExpFixEq::Id(var) => {
+                let j = Self::projection(equations, var)?;
+                if self.in_basis(b_i, j) {
+                    Ok(self.get_basis_elem_node(b_i, j))
+                } else {
+                    Err(ComposeError::new(format!(
+                        "{:?} is not in the basis of the variable {}",
+                        self.basis[b_i], var
+                    )))
+                }
+            }
         }
     }
 
+    /// An atom standing for the argument `i` of an operator, which is
+    /// replaced by the composition of the argument.
+    fn arg_node(b: usize, i: usize) -> Rc<Node<FormulaOperator>> {
+        Rc::new(Node {
+            val: FormulaOperator::Atom(BasisElem { b, i }),
+            children: vec![],
+        })
+    }
+
     #[inline]
-    fn logic_formula_to_tree(&self, formula: &LogicFormula) -> Rc<Node<FormulaOperator>> {
+    fn logic_formula_to_tree(
+        &self,
+        formula: &LogicFormula<B>,
+    ) -> Result<Rc<Node<FormulaOperator>>, ComposeError> {
         match formula {
-            LogicFormula::BasisElem(b, i) => {
-                self.get_basis_elem_node(*self.basis_map.get_by_right(b).unwrap(), *i)
-            }
+            LogicFormula::BasisElem(b, i) => match self.basis_index.get(b) {
+                Some(b) => Ok(Self::arg_node(*b, *i)),
+                None => Err(ComposeError::new(format!(
+                    "{:?} is not in the basis of any equation",
+                    b
+                ))),
+            },
             i @ LogicFormula::True | i @ LogicFormula::False => {
                 if matches!(i, LogicFormula::True) {
-                    self.true_node.clone()
+                    Ok(self.true_node.clone())
                 } else {
-                    self.false_node.clone()
+                    Ok(self.false_node.clone())
                 }
             }
             i @ LogicFormula::Conj(x) | i @ LogicFormula::Disj(x) => {
@@ -151,9 +225,12 @@ impl SymbolicExistsMoves {
                     } else {
                         FormulaOperator::Or
                     },
-                    children: x.iter().map(|a| self.logic_formula_to_tree(a)).collect(),
+                    children: x
+                        .iter()
+                        .map(|a| self.logic_formula_to_tree(a))
+                        .collect::<Result<_, _>>()?,
                 };
-                Rc::new(n)
+                Ok(Rc::new(n))
             }
         }
     }
@@ -163,9 +240,9 @@ impl SymbolicExistsMoves {
         &self,
         equations: &[FixEq],
         sub_exp: &ExpFixEq,
-        moves: &NotComposedMoves,
+        moves: &dyn MoveProvider<B>,
         curr_formula: &Rc<Node<FormulaOperator>>,
-    ) -> Rc<Node<FormulaOperator>> {
+    ) -> Result<Rc<Node<FormulaOperator>>, ComposeError> {
         match curr_formula.deref() {
             Node {
                 val: FormulaOperator::Atom(BasisElem { b, i }),
@@ -179,7 +256,15 @@ impl SymbolicExistsMoves {
                     ExpFixEq::Id(_) => vec![sub_exp.clone()],
                 };
 
-                self.compose_moves(equations, &args[*i], moves, *b)
+                match args.get(*i) {
+                    Some(arg) => self.compose_moves(equations, arg, moves, *b),
+                    None => Err(ComposeError::new(format!(
+                        "a move for {} refers to the argument {}, but there are {}",
+                        sub_exp,
+                        i + 1,
+                        args.len()
+                    ))),
+                }
             }
             Node {
                 val: val @ FormulaOperator::And,
@@ -196,18 +281,95 @@ impl SymbolicExistsMoves {
                     children: children
                         .iter()
                         .map(|a| self.subst(equations, sub_exp, moves, a))
-                        .collect(),
+                        .collect::<Result<_, _>>()?,
                 };
-                Rc::new(n)
+                Ok(Rc::new(n))
             }
         }
     }
 
     #[inline(always)]
-    fn projection(f: &[FixEq], curr_var: &String) -> usize {
+    fn projection(f: &[FixEq], curr_var: &String) -> Result<usize, ComposeError> {
         f.iter()
             .position(|FixEq { var, .. }| var == curr_var)
-            .unwrap()
+            .ok_or_else(|| ComposeError::new(format!("the variable {} is not defined", curr_var)))
+    }
+}
+
+impl<B: Hash + Eq + Clone> SymbolicExistsMoves<B> {
+    pub fn basis_index<Q>(&self, b: &Q) -> Option<usize>
+    where
+        B: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.basis_index.get(b).copied()
+    }
+
+    pub fn get_basis_elem(&self, b: usize) -> &B {
+        &self.basis[b]
+    }
+
+    /// The same moves, where each basis element is renamed by `name`, which
+    /// must be injective, e.g. to print the states of a transition system.
+    pub fn rename_basis<C: Hash + Eq + Clone>(
+        self,
+        name: impl Fn(&B) -> C,
+    ) -> SymbolicExistsMoves<C> {
+        let basis = self.basis.iter().map(name).collect::<Vec<_>>();
+        let basis_index = basis
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (b.clone(), i))
+            .collect();
+        SymbolicExistsMoves {
+            symbolic_moves: self.symbolic_moves,
+            basis,
+            basis_index,
+            eq_basis: self.eq_basis,
+            offset: self.offset,
+            basis_elem_node: self.basis_elem_node,
+            true_node: self.true_node,
+            false_node: self.false_node,
+        }
+    }
+
+    /// The number of elements in the bases of all the equations.
+    pub fn basis_len(&self) -> usize {
+        self.basis.len()
+    }
+
+    /// The indices of the elements of the basis of the equation `i`.
+    pub fn equation_basis(&self, i: usize) -> &[usize] {
+        &self.eq_basis[i]
+    }
+
+    /// Returns whether `(b, i)` is a position of the game, that is whether `b`
+    /// is in the basis of the equation `i`.
+    pub fn in_basis(&self, b: usize, i: usize) -> bool {
+        i < self.eq_basis.len() && self.slot(b, i).is_some()
+    }
+
+    /// The index of the position `(b, i)` in the tables of moves and atoms.
+    #[inline(always)]
+    fn slot(&self, b: usize, i: usize) -> Option<usize> {
+        let eq = &self.eq_basis[i];
+        if eq.len() == self.basis.len() {
+            Some(self.offset[i] + b)
+        } else {
+            eq.binary_search(&b).ok().map(|l| self.offset[i] + l)
+        }
+    }
+
+    pub fn get_true_atom(&self) -> Rc<Node<FormulaOperator>> {
+        self.true_node.clone()
+    }
+
+    pub fn get_false_atom(&self) -> Rc<Node<FormulaOperator>> {
+        self.false_node.clone()
+    }
+
+    pub fn get_basis_elem_node(&self, b: usize, i: usize) -> Rc<Node<FormulaOperator>> {
+        self.basis_elem_node[self.slot(b, i).unwrap()].clone()
     }
 
     #[inline]
@@ -270,9 +432,11 @@ impl SymbolicExistsMoves {
 
     #[inline(always)]
     pub fn get_formula(&self, b: usize, i: usize) -> Rc<Node<FormulaOperator>> {
-        self.symbolic_moves[i * self.basis_map.len() + b].clone()
+        self.symbolic_moves[self.slot(b, i).unwrap()].clone()
     }
+}
 
+impl<B: Hash + Eq + Clone + Display> SymbolicExistsMoves<B> {
     #[inline(always)]
     fn print_logic_formula(&self, n: &Rc<Node<FormulaOperator>>) -> String {
         match n.deref() {
@@ -291,7 +455,7 @@ impl SymbolicExistsMoves {
                 val: FormulaOperator::Atom(BasisElem { b, i }),
                 ..
             } => {
-                format!("[{}, {}]", self.basis_map.get_by_left(b).unwrap(), i + 1)
+                format!("[{}, {}]", self.basis[*b], i + 1)
             }
             Node { val, .. } => {
                 if matches!(val, FormulaOperator::And) {
@@ -304,44 +468,344 @@ impl SymbolicExistsMoves {
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct BasisElem {
     pub b: usize,
     pub i: usize,
 }
 
-#[derive(PartialEq, Eq, Debug, Clone)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub enum FormulaOperator {
     And,
     Or,
     Atom(BasisElem),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Node<T> {
     pub val: T,
     pub children: Vec<Rc<Node<T>>>,
 }
 
-impl Display for SymbolicExistsMoves {
+/// The serialised form of the composed moves. The formulas are flattened into
+/// a table of nodes, where the children come before their parents, so that
+/// the nodes which are shared, e.g. the atoms, are written once and shared
+/// again when read back. The index of the basis and the offsets of the
+/// equations are rebuilt when read.
+#[derive(Serialize, Deserialize)]
+struct Flattened<B, O> {
+    basis: Vec<B>,
+    eq_basis: Vec<Vec<usize>>,
+    nodes: Vec<(O, Vec<usize>)>,
+    symbolic_moves: Vec<usize>,
+    basis_elem_node: Vec<usize>,
+    true_node: usize,
+    false_node: usize,
+}
+
+/// The nodes of the formulas, each with the indices of its children,
+/// identified by their address so that shared nodes are added once.
+#[derive(Default)]
+struct NodeTable<'a> {
+    nodes: Vec<(&'a FormulaOperator, Vec<usize>)>,
+    index: HashMap<*const Node<FormulaOperator>, usize>,
+}
+
+impl<'a> NodeTable<'a> {
+    fn insert(&mut self, n: &'a Rc<Node<FormulaOperator>>) -> usize {
+        if let Some(i) = self.index.get(&Rc::as_ptr(n)) {
+            return *i;
+        }
+        let children = n.children.iter().map(|x| self.insert(x)).collect();
+        self.nodes.push((&n.val, children));
+        self.index.insert(Rc::as_ptr(n), self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+}
+
+impl<B: Serialize> Serialize for SymbolicExistsMoves<B> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut table = NodeTable::default();
+        let true_node = table.insert(&self.true_node);
+        let false_node = table.insert(&self.false_node);
+        let basis_elem_node = self
+            .basis_elem_node
+            .iter()
+            .map(|x| table.insert(x))
+            .collect();
+        let symbolic_moves = self
+            .symbolic_moves
+            .iter()
+            .map(|x| table.insert(x))
+            .collect();
+        Flattened {
+            basis: self.basis.iter().collect(),
+            eq_basis: self.eq_basis.clone(),
+            nodes: table.nodes,
+            symbolic_moves,
+            basis_elem_node,
+            true_node,
+            false_node,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, B: Deserialize<'de> + Hash + Eq + Clone> Deserialize<'de> for SymbolicExistsMoves<B> {
+    /// Fails if the moves are inconsistent, e.g. a node refers to a child
+    /// which is not defined before it, or an atom to a missing equation or to
+    /// an element outside the basis of its equation.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use de::Error;
+        let flat = Flattened::<B, FormulaOperator>::deserialize(deserializer)?;
+        // The basis of each equation is a sorted set, see `slot`
+        for (i, eq) in flat.eq_basis.iter().enumerate() {
+            if eq.windows(2).any(|w| w[0] >= w[1]) || eq.iter().any(|b| *b >= flat.basis.len()) {
+                return Err(D::Error::custom(format!(
+                    "the basis of the equation {} is not a set of basis elements",
+                    i
+                )));
+            }
+        }
+
+        let mut nodes: Vec<Rc<Node<FormulaOperator>>> = Vec::with_capacity(flat.nodes.len());
+        for (val, children) in flat.nodes {
+            if let FormulaOperator::Atom(BasisElem { b, i }) = &val {
+                if flat
+                    .eq_basis
+                    .get(*i)
+                    .is_none_or(|eq| eq.binary_search(b).is_err())
+                {
+                    return Err(D::Error::custom(format!(
+                        "the atom [{}, {}] is not defined",
+                        b, i
+                    )));
+                }
+            }
+            let children = children
+                .into_iter()
+                .map(|x| {
+                    nodes.get(x).cloned().ok_or_else(|| {
+                        D::Error::custom(format!("the node {} is used before it is defined", x))
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            nodes.push(Rc::new(Node { val, children }));
+        }
+        let node = |x: usize| {
+            nodes
+                .get(x)
+                .cloned()
+                .ok_or_else(|| D::Error::custom(format!("the node {} is not defined", x)))
+        };
+
+        let positions = flat.eq_basis.iter().map(Vec::len).sum::<usize>();
+        if flat.symbolic_moves.len() != positions || flat.basis_elem_node.len() != positions {
+            return Err(D::Error::custom(format!(
+                "expected a move and an atom for each of the {} positions",
+                positions
+            )));
+        }
+        let mut offset = Vec::with_capacity(flat.eq_basis.len());
+        let mut next = 0;
+        for eq in &flat.eq_basis {
+            offset.push(next);
+            next += eq.len();
+        }
+
+        Ok(SymbolicExistsMoves {
+            symbolic_moves: flat
+                .symbolic_moves
+                .into_iter()
+                .map(node)
+                .collect::<Result<_, _>>()?,
+            basis_index: flat
+                .basis
+                .iter()
+                .enumerate()
+                .map(|(i, b)| (b.clone(), i))
+                .collect(),
+            basis: flat.basis,
+            eq_basis: flat.eq_basis,
+            offset,
+            basis_elem_node: flat
+                .basis_elem_node
+                .into_iter()
+                .map(node)
+                .collect::<Result<_, _>>()?,
+            true_node: node(flat.true_node)?,
+            false_node: node(flat.false_node)?,
+        })
+    }
+}
+
+impl<B: Hash + Eq + Clone + Display> Display for SymbolicExistsMoves<B> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let l: Vec<_> = self
-            .symbolic_moves
+            .eq_basis
             .iter()
             .enumerate()
-            .map(|(i, x)| {
-                let b_i = self
-                    .basis_map
-                    .get_by_left(&(i % self.basis_map.len()))
-                    .unwrap();
-                format!(
-                    "phi({})({}) = {}",
-                    b_i,
-                    (i % self.m) + 1,
-                    self.print_logic_formula(x)
-                )
+            .flat_map(|(i, eq)| {
+                eq.iter().map(move |b| {
+                    format!(
+                        "phi({})({}) = {}",
+                        self.basis[*b],
+                        i + 1,
+                        self.print_logic_formula(&self.get_formula(*b, i))
+                    )
+                })
             })
             .collect();
         write!(f, "{}", l.join(";\n"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SymbolicExistsMoves;
+    use crate::algorithm::{LocalAlgorithm, Player};
+    use crate::ast::fixpoint_system::{ExpFixEq, FixEq, FixType};
+    use crate::ast::symbolic_moves::{LogicFormula, SymbolicExistsMoves as NotComposedMoves};
+    use crate::fixtures::{self, Example};
+    use crate::parse;
+
+    /// The states `0 -> 1 -> 0` and `2` of a transition system, where
+    /// `x =max diamond(x)` holds in the states from which an infinite path
+    /// starts.
+    fn cycle_system() -> (Vec<u32>, Vec<FixEq>) {
+        let fix_system = vec![FixEq {
+            var: "x".to_string(),
+            fix_ty: FixType::Max,
+            basis: None,
+            exp: ExpFixEq::Operator("diamond".to_string(), vec![ExpFixEq::Id("x".to_string())]),
+        }];
+        (vec![0, 1, 2], fix_system)
+    }
+
+    fn assert_cycle_solution(fix_system: &[FixEq], moves: &SymbolicExistsMoves<u32>) {
+        let algo = LocalAlgorithm {
+            fix_system,
+            symbolic_moves: moves,
+            up_to: None,
+        };
+        assert_eq!(algo.local_check(0, 0).unwrap(), Player::Eve);
+        assert_eq!(algo.local_check(1, 0).unwrap(), Player::Eve);
+        assert_eq!(algo.local_check(2, 0).unwrap(), Player::Adam);
+    }
+
+    /// The basis may be any type, here the states of `cycle_system`, with the
+    /// moves read from a table. The moves of the state `2`, which is not in
+    /// the table, are `false`.
+    #[test]
+    fn compose_integer_basis() {
+        let (basis, fix_system) = cycle_system();
+        let moves = NotComposedMoves {
+            basis_map: basis[..2]
+                .iter()
+                .enumerate()
+                .map(|(i, b)| (*b, i))
+                .collect(),
+            fun_map: [("diamond".to_string(), 0)].into_iter().collect(),
+            formulas: vec![LogicFormula::BasisElem(1, 0), LogicFormula::BasisElem(0, 0)],
+        };
+        let moves = SymbolicExistsMoves::compose(&fix_system, &moves, &basis).unwrap();
+        assert_cycle_solution(&fix_system, &moves);
+    }
+
+    /// As `compose_integer_basis`, with the moves computed on demand by a
+    /// closure rather than read from a table.
+    #[test]
+    fn compose_move_provider() {
+        let (basis, fix_system) = cycle_system();
+        let successor = |b: &u32, _: &str| match b {
+            0 => LogicFormula::BasisElem(1, 0),
+            1 => LogicFormula::BasisElem(0, 0),
+            _ => LogicFormula::False,
+        };
+        let moves = SymbolicExistsMoves::compose(&fix_system, &successor, &basis).unwrap();
+        assert_cycle_solution(&fix_system, &moves);
+    }
+
+    /// In `tests/example_mixed` the variable `x` ranges over booleans, with
+    /// the basis `true`, and `y` over sets of states: `x` holds whenever some
+    /// state has an infinite path.
+    #[test]
+    fn compose_heterogeneous_bases() {
+        let src = |f| fixtures::src("example_mixed", f);
+        let arity = parse::parse_fun_arity(src("arity")).unwrap();
+        let fix_system = parse::parse_fixpoint_system(&arity, src("fix_system")).unwrap();
+        let states = parse::parse_basis(src("basis")).unwrap();
+        let booleans = parse::parse_basis(src("bool")).unwrap();
+        let union = [states.clone(), booleans.clone()].concat();
+        let moves = parse::parse_symbolic_system(&arity, &union, src("symbolic")).unwrap();
+
+        let bases = vec![booleans, states];
+        let moves = SymbolicExistsMoves::compose_with_bases(&fix_system, &moves, &bases).unwrap();
+        assert!(!moves.in_basis(moves.basis_index("{a}").unwrap(), 0));
+        let algo = LocalAlgorithm {
+            fix_system: &fix_system,
+            symbolic_moves: &moves,
+            up_to: None,
+        };
+        assert_eq!(
+            algo.local_check("true".to_string(), 0).unwrap(),
+            Player::Eve
+        );
+        assert_eq!(algo.local_check("{a}".to_string(), 1).unwrap(), Player::Eve);
+        assert_eq!(
+            algo.local_check("{c}".to_string(), 1).unwrap(),
+            Player::Adam
+        );
+        assert!(algo.local_check("{a}".to_string(), 0).is_err());
+        assert!(algo.local_check("{f}".to_string(), 1).is_err());
+        assert!(algo.local_check("{a}".to_string(), 2).is_err());
+
+        // A move of x which leads to an element outside the basis of y
+        let wrong = src("symbolic").replace("[{a}, 1] or", "[true, 1] or");
+        let wrong = parse::parse_symbolic_system(&arity, &union, wrong).unwrap();
+        assert!(SymbolicExistsMoves::compose_with_bases(&fix_system, &wrong, &bases).is_err());
+    }
+
+    /// The composed moves are the same once written and read back, and
+    /// inconsistent ones are refused.
+    #[test]
+    fn serialize_composed_moves() {
+        let example = Example::load("example_01");
+        let moves = &example.moves;
+
+        let json = serde_json::to_string(moves).unwrap();
+        let read: SymbolicExistsMoves = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.to_string(), moves.to_string());
+        let algo = |moves| LocalAlgorithm {
+            symbolic_moves: moves,
+            ..example.algorithm()
+        };
+        for b in &example.basis {
+            for i in 0..example.fix_system.len() {
+                assert_eq!(
+                    algo(&read).local_check(b.to_owned(), i).unwrap(),
+                    algo(moves).local_check(b.to_owned(), i).unwrap()
+                );
+            }
+        }
+
+        let wrong = json.replace("\"true_node\":0", "\"true_node\":100000");
+        assert_ne!(wrong, json);
+        assert!(serde_json::from_str::<SymbolicExistsMoves>(&wrong).is_err());
+
+        // An atom whose element is in the basis, but not in the one of its
+        // equation
+        let mut flat: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let basis = flat["basis"].as_array_mut().unwrap();
+        basis.push("{f}".into());
+        let b = basis.len() - 1;
+        let atom = flat["nodes"]
+            .as_array_mut()
+            .unwrap()
+            .iter_mut()
+            .find_map(|node| node[0].get_mut("Atom"))
+            .unwrap();
+        atom["b"] = b.into();
+        assert!(serde_json::from_str::<SymbolicExistsMoves>(&flat.to_string()).is_err());
+    }
+}
@@ -1,18 +1,84 @@
 mod parser;
 mod powerset_game;
+mod zielonka;
 
 pub mod ast;
 pub mod normalizer;
+pub mod problem;
+pub mod validation;
 pub mod parse {
     pub use crate::parser::parse_basis;
+    pub use crate::parser::parse_certificate;
     pub use crate::parser::parse_fixpoint_system;
     pub use crate::parser::parse_fun_arity;
+    pub use crate::parser::parse_lattice;
+    pub use crate::parser::parse_operators;
+    pub use crate::parser::parse_symbolic_list;
     pub use crate::parser::parse_symbolic_system;
+    pub use crate::parser::Diagnostic;
     pub use crate::parser::ParserError;
 }
 
 pub mod algorithm {
+    pub use crate::powerset_game::certificate::{CertPos, Certificate, CertificateError};
+    pub use crate::powerset_game::checkpoint::{Checkpoint, CheckpointError, SaveCheckpoint};
     pub use crate::powerset_game::player::Player;
     pub use crate::powerset_game::position::{AdamPos, EvePos, Position};
+    pub use crate::powerset_game::query::{Query, QueryError};
+    pub use crate::powerset_game::up_to::{UpTo, UpToEquivalence};
     pub use crate::powerset_game::LocalAlgorithm;
+    pub use crate::zielonka::{ParityArena, ParitySolution};
+}
+
+#[cfg(test)]
+mod fixtures {
+    use crate::algorithm::LocalAlgorithm;
+    use crate::ast::fixpoint_system::FixEq;
+    use crate::ast::symbolic_moves_composed::SymbolicExistsMoves;
+    use crate::parse;
+
+    /// Reads the file `file` of the example `example` in `tests`.
+    pub fn src(example: &str, file: &str) -> String {
+        std::fs::read_to_string(format!(
+            "{}/../../tests/{}/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            example,
+            file
+        ))
+        .unwrap()
+    }
+
+    /// An example in `tests` over a single basis, with its symbolic
+    /// exists-moves composed.
+    pub struct Example {
+        pub arity: Vec<(String, usize)>,
+        pub fix_system: Vec<FixEq>,
+        pub basis: Vec<String>,
+        pub moves: SymbolicExistsMoves,
+    }
+
+    impl Example {
+        pub fn load(example: &str) -> Example {
+            let src = |file| src(example, file);
+            let arity = parse::parse_fun_arity(src("arity")).unwrap();
+            let fix_system = parse::parse_fixpoint_system(&arity, src("fix_system")).unwrap();
+            let basis = parse::parse_basis(src("basis")).unwrap();
+            let moves = parse::parse_symbolic_system(&arity, &basis, src("symbolic")).unwrap();
+            let moves = SymbolicExistsMoves::compose(&fix_system, &moves, &basis).unwrap();
+            Example {
+                arity,
+                fix_system,
+                basis,
+                moves,
+            }
+        }
+
+        pub fn algorithm(&self) -> LocalAlgorithm<'_> {
+            LocalAlgorithm {
+                fix_system: &self.fix_system,
+                symbolic_moves: &self.moves,
+                up_to: None,
+            }
+        }
+    }
 }
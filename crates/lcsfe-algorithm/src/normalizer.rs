@@ -7,32 +7,52 @@ pub fn normalize_system(fix_system: Vec<FixEq>) -> (Vec<FixEq>, HashMap<String,
         .enumerate()
         .map(|(i, FixEq { var, .. })| (var.to_owned(), format!("x_{}", i + 1)))
         .collect::<HashMap<String, String>>();
+    let bases = fix_system
+        .iter()
+        .map(|eq| (eq.var.to_owned(), eq.basis.clone()))
+        .collect::<HashMap<_, _>>();
 
     let normalized_system = fix_system
         .into_iter()
-        .flat_map(|fix_eq| normalize_equation(fix_eq, &mut var_map))
+        .flat_map(|fix_eq| normalize_equation(fix_eq, &mut var_map, &bases))
         .collect::<Vec<_>>();
 
     (normalized_system, var_map)
 }
 
 fn normalize_equation(
-    FixEq { var, fix_ty, exp }: FixEq,
+    FixEq {
+        var,
+        fix_ty,
+        exp,
+        basis,
+    }: FixEq,
     var_map: &mut HashMap<String, String>,
+    bases: &HashMap<String, Option<String>>,
 ) -> Vec<FixEq> {
     match exp {
         ExpFixEq::Id(x) => vec![FixEq {
             var: var_map.get(&var).cloned().unwrap(),
             fix_ty,
+            basis,
             exp: var_map.get(&x).cloned().map(ExpFixEq::Id).unwrap(),
         }],
 
         ExpFixEq::Operator(name, args) => {
-            let normalized_args = normalize_args(fix_ty.clone(), args, var_map);
+            // An argument of an operator may range over another basis
+            let args = args
+                .into_iter()
+                .map(|arg| {
+                    let arg_basis = operand_basis(&arg, bases).unwrap_or_else(|| basis.clone());
+                    (arg, arg_basis)
+                })
+                .collect();
+            let normalized_args = normalize_args(fix_ty.clone(), args, var_map, bases);
 
             let mut op_normalized = vec![FixEq {
                 var: var_map.get(&var).cloned().unwrap(),
                 fix_ty,
+                basis,
                 exp: ExpFixEq::Operator(
                     name,
                     normalized_args
@@ -45,10 +65,16 @@ fn normalize_equation(
             op_normalized
         }
         ExpFixEq::And(l, r) => {
-            let normalized_args = normalize_args(fix_ty.clone(), vec![*l, *r], var_map);
+            let normalized_args = normalize_args(
+                fix_ty.clone(),
+                vec![(*l, basis.clone()), (*r, basis.clone())],
+                var_map,
+                bases,
+            );
             let mut and_normalized = vec![FixEq {
                 var: var_map.get(&var).cloned().unwrap(),
                 fix_ty,
+                basis,
                 exp: ExpFixEq::And(
                     Box::new(
                         var_map
@@ -70,10 +96,16 @@ fn normalize_equation(
             and_normalized
         }
         ExpFixEq::Or(l, r) => {
-            let normalized_args = normalize_args(fix_ty.clone(), vec![*l, *r], var_map);
+            let normalized_args = normalize_args(
+                fix_ty.clone(),
+                vec![(*l, basis.clone()), (*r, basis.clone())],
+                var_map,
+                bases,
+            );
             let mut or_normalized = vec![FixEq {
                 var: var_map.get(&var).cloned().unwrap(),
                 fix_ty,
+                basis,
                 exp: ExpFixEq::Or(
                     Box::new(
                         var_map
@@ -99,15 +131,17 @@ fn normalize_equation(
 
 fn normalize_args(
     fix_ty: FixType,
-    args: Vec<ExpFixEq>,
+    args: Vec<(ExpFixEq, Option<String>)>,
     var_map: &mut HashMap<String, String>,
+    bases: &HashMap<String, Option<String>>,
 ) -> Vec<Vec<FixEq>> {
     args.into_iter()
-        .map(|arg| match &arg {
+        .map(|(arg, basis)| match &arg {
             ExpFixEq::Id(x) => vec![FixEq {
                 var: x.to_owned(),
                 fix_ty: fix_ty.clone(),
                 exp: arg,
+                basis,
             }],
             _ => {
                 let new_var = format!("x_{}", var_map.len() + 1);
@@ -117,14 +151,41 @@ fn normalize_args(
                         var: new_var,
                         fix_ty: fix_ty.clone(),
                         exp: arg,
+                        basis,
                     },
                     var_map,
+                    bases,
                 )
             }
         })
         .collect::<Vec<_>>()
 }
 
+/// The basis of the variables in the argument `exp` of an operator, if they
+/// all range over the same one, e.g. the basis of `y` for `diamond(y)` in
+/// `x : bool =max some(diamond(y))`. The composition checks the moves against
+/// the bases, so a wrong guess is reported rather than solved.
+fn operand_basis(
+    exp: &ExpFixEq,
+    bases: &HashMap<String, Option<String>>,
+) -> Option<Option<String>> {
+    fn variables<'a>(exp: &'a ExpFixEq, vars: &mut Vec<&'a String>) {
+        match exp {
+            ExpFixEq::Id(x) => vars.push(x),
+            ExpFixEq::And(l, r) | ExpFixEq::Or(l, r) => {
+                variables(l, vars);
+                variables(r, vars);
+            }
+            ExpFixEq::Operator(_, args) => args.iter().for_each(|arg| variables(arg, vars)),
+        }
+    }
+    let mut vars = vec![];
+    variables(exp, &mut vars);
+    let mut operand_bases = vars.into_iter().filter_map(|x| bases.get(x));
+    let first = operand_bases.next()?;
+    operand_bases.all(|b| b == first).then(|| first.clone())
+}
+
 fn flatten_and_remove_identity(new_fix_eq: Vec<Vec<FixEq>>) -> impl Iterator<Item = FixEq> {
     new_fix_eq
         .into_iter()
@@ -164,11 +225,13 @@ mod tests {
             FixEq {
                 var: "x_1".to_owned(),
                 fix_ty: FixType::Max,
+                basis: None,
                 exp: ExpFixEq::Id("x_2".to_owned()),
             },
             FixEq {
                 var: "x_2".to_owned(),
                 fix_ty: FixType::Min,
+                basis: None,
                 exp: ExpFixEq::Or(
                     Box::new(ExpFixEq::Or(
                         Box::new(ExpFixEq::Id("x_1".to_owned())),
@@ -180,16 +243,19 @@ mod tests {
             FixEq {
                 var: "x_3".to_owned(),
                 fix_ty: FixType::Max,
+                basis: None,
                 exp: ExpFixEq::Id("x_4".to_owned()),
             },
             FixEq {
                 var: "x_4".to_owned(),
                 fix_ty: FixType::Min,
+                basis: None,
                 exp: ExpFixEq::Id("x_5".to_owned()),
             },
             FixEq {
                 var: "x_5".to_owned(),
                 fix_ty: FixType::Max,
+                basis: None,
                 exp: ExpFixEq::And(
                     Box::new(ExpFixEq::Id("x_3".to_string())),
                     Box::new(ExpFixEq::Operator(
@@ -207,11 +273,13 @@ mod tests {
             FixEq {
                 var: "x_1".to_owned(),
                 fix_ty: FixType::Max,
+                basis: None,
                 exp: ExpFixEq::Id("x_2".to_owned()),
             },
             FixEq {
                 var: "x_2".to_owned(),
                 fix_ty: FixType::Min,
+                basis: None,
                 exp: ExpFixEq::Or(
                     Box::new(ExpFixEq::Id("x_6".to_owned())),
                     Box::new(ExpFixEq::Id("x_3".to_owned())),
@@ -220,6 +288,7 @@ mod tests {
             FixEq {
                 var: "x_6".to_owned(),
                 fix_ty: FixType::Min,
+                basis: None,
                 exp: ExpFixEq::Or(
                     Box::new(ExpFixEq::Id("x_1".to_owned())),
                     Box::new(ExpFixEq::Id("x_2".to_owned())),
@@ -228,16 +297,19 @@ mod tests {
             FixEq {
                 var: "x_3".to_owned(),
                 fix_ty: FixType::Max,
+                basis: None,
                 exp: ExpFixEq::Id("x_4".to_owned()),
             },
             FixEq {
                 var: "x_4".to_owned(),
                 fix_ty: FixType::Min,
+                basis: None,
                 exp: ExpFixEq::Id("x_5".to_owned()),
             },
             FixEq {
                 var: "x_5".to_owned(),
                 fix_ty: FixType::Max,
+                basis: None,
                 exp: ExpFixEq::And(
                     Box::new(ExpFixEq::Id("x_3".to_owned())),
                     Box::new(ExpFixEq::Id("x_7".to_owned())),
@@ -246,11 +318,13 @@ mod tests {
             FixEq {
                 var: "x_7".to_owned(),
                 fix_ty: FixType::Max,
+                basis: None,
                 exp: ExpFixEq::Operator("diamond".to_owned(), vec![ExpFixEq::Id("x_8".to_owned())]),
             },
             FixEq {
                 var: "x_8".to_owned(),
                 fix_ty: FixType::Max,
+                basis: None,
                 exp: ExpFixEq::And(
                     Box::new(ExpFixEq::Id("x_4".to_owned())),
                     Box::new(ExpFixEq::Id("x_5".to_owned())),
@@ -261,4 +335,44 @@ mod tests {
         let (fun_normalized_system, _) = normalizer::normalize_system(system);
         assert_eq!(fun_normalized_system, normalized_system)
     }
+
+    /// x : bool =max some(diamond(y))
+    /// y =max diamond(y)
+    ///
+    /// which normalized renames x and y to x_1 and x_2, and the variable x_3
+    /// introduced for `diamond(y)` ranges over the basis of y rather than over
+    /// the one of x.
+    #[test]
+    fn normalize_system_operand_basis() {
+        let system = vec![
+            FixEq {
+                var: "x".to_owned(),
+                fix_ty: FixType::Max,
+                basis: Some("bool".to_owned()),
+                exp: ExpFixEq::Operator(
+                    "some".to_owned(),
+                    vec![ExpFixEq::Operator(
+                        "diamond".to_owned(),
+                        vec![ExpFixEq::Id("y".to_owned())],
+                    )],
+                ),
+            },
+            FixEq {
+                var: "y".to_owned(),
+                fix_ty: FixType::Max,
+                basis: None,
+                exp: ExpFixEq::Operator("diamond".to_owned(), vec![ExpFixEq::Id("y".to_owned())]),
+            },
+        ];
+
+        let (normalized_system, _) = normalizer::normalize_system(system);
+        let bases = normalized_system
+            .iter()
+            .map(|eq| (eq.var.as_str(), eq.basis.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            bases,
+            vec![("x_1", Some("bool")), ("x_3", None), ("x_2", None)]
+        );
+    }
 }
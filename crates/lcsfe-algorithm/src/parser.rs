@@ -2,17 +2,164 @@ use std::fmt;
 
 mod arity_parser;
 mod basis_parser;
+mod certificate_parser;
 mod eq_system_parser;
+mod lattice_parser;
 mod moves_parser;
+mod operators_parser;
 
 #[derive(Debug, Clone)]
 pub struct ParserError {
     details: String,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// A parse error located in the source, along with the line it occurs in,
+/// so that it can be displayed on its own.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// The line of the error, starting from 1
+    pub line: usize,
+    /// The column of the error, starting from 1
+    pub column: usize,
+    /// How many characters, from the column, the error refers to
+    pub width: usize,
+    /// The text of the line
+    pub source_line: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Locates the characters `span` of `src`.
+    pub fn new(src: &str, span: std::ops::Range<usize>, message: String) -> Diagnostic {
+        let before = src.chars().take(span.start).collect::<String>();
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+        let source_line = src.lines().nth(line - 1).unwrap_or_default().to_owned();
+        Diagnostic {
+            line,
+            column,
+            width: span.len().max(1),
+            source_line,
+            message,
+        }
+    }
+
+    /// An error which refers to the whole line `line` of a line based
+    /// format.
+    pub fn on_line(line: usize, source_line: &str, message: String) -> Diagnostic {
+        let indent = source_line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .count();
+        Diagnostic {
+            line,
+            column: indent + 1,
+            width: source_line.trim().chars().count().max(1),
+            source_line: source_line.to_owned(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self
+            .width
+            .min(
+                self.source_line
+                    .chars()
+                    .count()
+                    .saturating_sub(self.column - 1),
+            )
+            .max(1);
+        write!(
+            f,
+            "line {}, column {}: {}\n    {}\n    {}{}",
+            self.line,
+            self.column,
+            self.message,
+            self.source_line,
+            " ".repeat(self.column - 1),
+            "^".repeat(width)
+        )
+    }
 }
 
 impl ParserError {
     pub fn new(details: String) -> ParserError {
-        ParserError { details }
+        ParserError {
+            details,
+            diagnostics: vec![],
+        }
+    }
+
+    pub fn from_diagnostics(diagnostics: Vec<Diagnostic>) -> ParserError {
+        ParserError {
+            details: diagnostics
+                .iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<String>>()
+                .join("\n- "),
+            diagnostics,
+        }
+    }
+
+    /// A single error listing all of `errs`, located if they all are.
+    pub fn merge(errs: Vec<ParserError>) -> ParserError {
+        if errs.iter().all(|e| !e.diagnostics.is_empty()) {
+            Self::from_diagnostics(errs.into_iter().flat_map(|e| e.diagnostics).collect())
+        } else {
+            ParserError::new(
+                errs.into_iter()
+                    .map(|e| e.details)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        }
+    }
+
+    /// Locates in `src` the errors returned by a chumsky parser, listing the
+    /// expected tokens.
+    pub fn from_simple(src: &str, errs: Vec<Simple<char>>) -> ParserError {
+        Self::from_diagnostics(
+            errs.into_iter()
+                .map(|e| Diagnostic::new(src, e.span(), simple_message(&e)))
+                .collect(),
+        )
+    }
+
+    /// The located errors, empty if the error is not about the syntax.
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+}
+
+fn simple_message(e: &Simple<char>) -> String {
+    let token = |x: &Option<char>| match x {
+        Some(c) => format!("{:?}", c.to_string()),
+        None => "end of input".to_string(),
+    };
+    let found = token(&e.found().copied());
+    let mut expected = e.expected().map(token).collect::<Vec<_>>();
+    expected.sort();
+    let expected = match expected.len() {
+        0 => String::new(),
+        1 => format!(" but expected {}", expected[0]),
+        _ => format!(" but expected one of {}", expected.join(", ")),
+    };
+    match e.reason() {
+        // The messages of custom errors are not displayed by chumsky
+        chumsky::error::SimpleReason::Custom(msg) => msg.to_owned(),
+        chumsky::error::SimpleReason::Unclosed { delimiter, .. } => {
+            format!(
+                "unclosed delimiter {:?}, found {}{}",
+                delimiter.to_string(),
+                found,
+                expected
+            )
+        }
+        chumsky::error::SimpleReason::Unexpected => format!("found {}{}", found, expected),
     }
 }
 
@@ -24,33 +171,63 @@ impl std::error::Error for ParserError {
 
 impl fmt::Display for ParserError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "Parse error(s):\n{}", self.details)
+        if self.diagnostics.is_empty() {
+            write!(f, "Parse error(s):\n{}", self.details)
+        } else {
+            write!(f, "Parse error(s):\n- {}", self.details)
+        }
     }
 }
 
 use crate::ast::fixpoint_system::FixEq;
-use crate::ast::symbolic_moves::SymbolicExistsMoves;
+use crate::ast::lattice::Lattice;
+use crate::ast::operator_table::OperatorTable;
+use crate::ast::symbolic_moves::{LogicFormula, SymbolicExistsMoves};
+use crate::powerset_game::certificate::Certificate;
 use chumsky::prelude::*;
 
 pub fn parse_basis(src: String) -> Result<Vec<String>, Vec<ParserError>> {
     Ok(basis_parser::basis_parser(src))
 }
 
+/// Parses the order relation of a finite lattice, and checks that it is
+/// actually a lattice.
+pub fn parse_lattice(src: String) -> Result<Lattice, ParserError> {
+    let (elements, below) = lattice_parser::lattice_parser()
+        .parse(src.as_str())
+        .map_err(|errs| ParserError::from_simple(&src, errs))?;
+    Lattice::new(elements, &below).map_err(|e| ParserError::new(e.to_string()))
+}
+
 pub fn parse_symbolic_system(
     arity: &[(String, usize)],
     basis: &[String],
     src: String,
 ) -> Result<SymbolicExistsMoves, ParserError> {
+    let moves = parse_symbolic_list(arity, basis, src)?;
+    Ok(SymbolicExistsMoves::from_list(arity, basis, moves))
+}
+
+/// Parses the symbolic exists-moves as they are listed in the source, e.g.
+/// to check them with `validation::validate_moves` before building the table.
+pub fn parse_symbolic_list(
+    arity: &[(String, usize)],
+    basis: &[String],
+    src: String,
+) -> Result<Vec<(String, String, LogicFormula)>, ParserError> {
     moves_parser::symbolic_moves_parser(arity, basis)
-        .parse(src)
-        .map_err(|errs| {
-            ParserError::new(
-                errs.into_iter()
-                    .map(|e| e.to_string())
-                    .collect::<Vec<String>>()
-                    .join("\n- "),
-            )
-        })
+        .parse(src.as_str())
+        .map_err(|errs| ParserError::from_simple(&src, errs))
+}
+
+pub fn parse_operators(
+    arity: &[(String, usize)],
+    basis: &[String],
+    src: String,
+) -> Result<Vec<OperatorTable>, ParserError> {
+    operators_parser::operators_parser(arity, basis)
+        .parse(src.as_str())
+        .map_err(|errs| ParserError::from_simple(&src, errs))
 }
 
 pub fn parse_fixpoint_system(
@@ -58,24 +235,45 @@ pub fn parse_fixpoint_system(
     src: String,
 ) -> Result<Vec<FixEq>, ParserError> {
     eq_system_parser::eq_system_parser(arity)
-        .parse(src)
-        .map_err(|errs| {
-            ParserError::new(
-                errs.into_iter()
-                    .map(|e| e.to_string())
-                    .collect::<Vec<String>>()
-                    .join("\n- "),
-            )
-        })
+        .parse(src.as_str())
+        .map_err(|errs| ParserError::from_simple(&src, errs))
 }
 
 pub fn parse_fun_arity(src: String) -> Result<Vec<(String, usize)>, ParserError> {
-    arity_parser::arity_parser().parse(src).map_err(|errs| {
-        ParserError::new(
-            errs.into_iter()
-                .map(|e| e.to_string())
-                .collect::<Vec<String>>()
-                .join("\n- "),
-        )
-    })
+    arity_parser::arity_parser()
+        .parse(src.as_str())
+        .map_err(|errs| ParserError::from_simple(&src, errs))
+}
+
+pub fn parse_certificate(basis: &[String], src: String) -> Result<Certificate, ParserError> {
+    certificate_parser::certificate_parser(basis)
+        .parse(src.as_str())
+        .map_err(|errs| ParserError::from_simple(&src, errs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_certificate, parse_fixpoint_system, parse_fun_arity};
+
+    #[test]
+    fn diagnostics_are_located() {
+        let arity = parse_fun_arity("and 2\nor 2".to_string()).unwrap();
+        let err = parse_fixpoint_system(&arity, "x =max and(x, y);\ny =min or(x y);".to_string())
+            .unwrap_err();
+        let diagnostic = &err.diagnostics()[0];
+        assert_eq!((diagnostic.line, diagnostic.column), (2, 10));
+        assert_eq!(diagnostic.source_line, "y =min or(x y);");
+        assert!(err.to_string().contains("\n             ^"));
+    }
+
+    /// A certificate is untrusted, so an index which is not positive, or
+    /// which does not fit, is a parse error rather than a panic.
+    #[test]
+    fn certificate_indices_are_positive() {
+        let basis = vec!["a".to_string()];
+        let cert = |i: &str| parse_certificate(&basis, format!("winner eve; query [a, {}];", i));
+        assert_eq!(cert("1").unwrap().query.i, 0);
+        assert!(cert("0").is_err());
+        assert!(cert("99999999999999999999999").is_err());
+    }
 }
@@ -0,0 +1,75 @@
+use chumsky::prelude::*;
+
+use crate::powerset_game::certificate::{CertPos, Certificate};
+use crate::powerset_game::player::Player;
+
+///
+/// Returns a parser for the following grammar:
+///
+// <Cert>     ::= `winner' <Player> `;' `query' <Pos> `;' <MoveList>
+// <Player>   ::= `eve' | `adam'
+// <MoveList> ::= <Move> <MoveList> | <Move>
+// <Move>     ::= <Pos> `->' <Pos> (`,' <Pos>)* `;' | <Pos> `->' `;'
+// <Pos>      ::= `[' <Id> `,' <Num> `]'
+// <Id>       ::= ( an element of the basis )
+// <Num>      ::= ( a natural number )
+///
+/// Which is the format `Certificate` is printed with. Note that the index of
+/// an equation starts from 1, as it does for the symbolic exists-moves.
+///
+pub fn certificate_parser(
+    basis: &[String],
+) -> impl Parser<char, Certificate, Error = Simple<char>> + '_ {
+    let basis_parser = basis
+        .iter()
+        .map(|str| just(str.clone()).padded())
+        .collect::<Vec<_>>();
+
+    let pos = choice(basis_parser)
+        .then_ignore(just(','))
+        .then(
+            text::int(10)
+                .padded()
+                .try_map(|int: String, span| match int.parse::<usize>() {
+                    Ok(i) if i > 0 => Ok(i - 1),
+                    _ => Err(Simple::custom(
+                        span,
+                        format!("the index {} is not a positive index", int),
+                    )),
+                }),
+        )
+        .delimited_by(just('['), just(']'))
+        .padded()
+        .map(|(b, i)| CertPos { b, i });
+
+    let winner = text::keyword("winner")
+        .padded()
+        .ignore_then(
+            text::keyword("eve")
+                .to(Player::Eve)
+                .or(text::keyword("adam").to(Player::Adam))
+                .padded(),
+        )
+        .then_ignore(just(';'));
+
+    let query = text::keyword("query")
+        .padded()
+        .ignore_then(pos.clone())
+        .then_ignore(just(';'));
+
+    let cert_move = pos
+        .clone()
+        .then_ignore(just("->").padded())
+        .then(pos.separated_by(just(',')))
+        .then_ignore(just(';'));
+
+    winner
+        .then(query)
+        .then(cert_move.padded().repeated())
+        .map(|((winner, query), moves)| Certificate {
+            winner,
+            query,
+            moves,
+        })
+        .then_ignore(end())
+}
@@ -21,6 +21,10 @@ use chumsky::prelude::*;
 /// Tokens `ID` and `OP` are a strings, the latter represents the name of an operator provided as input to the parser.
 /// If the goal is to parse mu-calculus formulae, a possible definition for `OP` would be `OP in {'diamond', 'box'}`.
 ///
+/// An equation may declare the basis of the lattice its variable ranges over,
+/// as in `x : states =max diamond(x);`, otherwise it ranges over the default
+/// basis.
+///
 /// > Note that the library `Chumsky`, and in general have a limited support for left recursion.
 ///
 pub fn eq_system_parser(
@@ -71,15 +75,23 @@ pub fn eq_system_parser(
 
     let fix_type = |c| just(c).padded();
 
+    let basis = just(':').ignore_then(text::ident().padded()).or_not();
+
     let equation = text::ident()
         .padded()
+        .then(basis)
         .then(
             fix_type("=max")
                 .to(FixType::Max)
                 .or(fix_type("=min").to(FixType::Min)),
         )
         .then(expr.clone())
-        .map(|((var, fix_ty), exp)| FixEq { var, fix_ty, exp });
+        .map(|(((var, basis), fix_ty), exp)| FixEq {
+            var,
+            fix_ty,
+            exp,
+            basis,
+        });
 
     let system_of_equations = equation
         .clone()
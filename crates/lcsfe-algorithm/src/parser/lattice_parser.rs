@@ -0,0 +1,45 @@
+use chumsky::prelude::*;
+
+///
+/// Returns a parser for the following grammar:
+///
+// <Lattice> ::= <Chain> <Lattice> | <Chain>
+// <Chain>   ::= <Elem> (`<' <Elem>)* `;'
+// <Elem>    ::= ( any string without whitespaces, `<' and `;' )
+///
+/// Each chain `x_1 < x_2 < ... < x_n` states that every element is below
+/// the next one, e.g. it lists edges of the Hasse diagram. A chain with a
+/// single element declares it. The parser returns the elements, in order of
+/// appearance, and the pairs of the order relation.
+///
+pub fn lattice_parser(
+) -> impl Parser<char, (Vec<String>, Vec<(String, String)>), Error = Simple<char>> {
+    let elem = filter(|c: &char| !c.is_whitespace() && *c != '<' && *c != ';')
+        .repeated()
+        .at_least(1)
+        .collect::<String>()
+        .padded();
+
+    let chain = elem
+        .separated_by(just('<'))
+        .at_least(1)
+        .then_ignore(just(';').padded());
+
+    chain.repeated().then_ignore(end()).map(|chains| {
+        let mut elements: Vec<String> = vec![];
+        let mut below = vec![];
+        for chain in chains {
+            for x in &chain {
+                if !elements.contains(x) {
+                    elements.push(x.to_owned());
+                }
+            }
+            below.extend(
+                chain
+                    .windows(2)
+                    .map(|xs| (xs[0].to_owned(), xs[1].to_owned())),
+            );
+        }
+        (elements, below)
+    })
+}
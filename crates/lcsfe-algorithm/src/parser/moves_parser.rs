@@ -1,8 +1,6 @@
-use rustc_hash::FxHashMap as HashMap;
-
 use chumsky::prelude::*;
 
-use crate::ast::symbolic_moves::{LogicFormula, SymbolicExistsMoves};
+use crate::ast::symbolic_moves::LogicFormula;
 ///
 /// Returns a parser for the following grammar:
 ///
@@ -20,24 +18,35 @@ use crate::ast::symbolic_moves::{LogicFormula, SymbolicExistsMoves};
 /// Where `ID in String` and `true`, `false` are respectively syntactic sugar for an empty conjunction and
 /// disjunction.
 ///
+/// The parser returns the moves in order of appearance, as triples of a
+/// basis element, an operator and a formula.
+///
 /// > Note that the library `Chumsky`, and in general parser combinators libraries
 /// > have a limited support for left recursion.
 ///
 pub fn symbolic_moves_parser<'a>(
     fun_with_arities: &'a [(String, usize)],
     basis: &'a [String],
-) -> impl Parser<char, SymbolicExistsMoves, Error = Simple<char>> + 'a {
+) -> impl Parser<char, Vec<(String, String, LogicFormula)>, Error = Simple<char>> + 'a {
     let basis_parser = basis
         .iter()
         .map(|str| just(str.clone()).padded())
         .collect::<Vec<_>>();
 
     let logic_formula = recursive(|logic_formula| {
-        let base_elem = (choice(basis_parser.clone())
-            .then_ignore(just(','))
-            .then(text::int(10).padded()))
+        let base_elem = (choice(basis_parser.clone()).then_ignore(just(',')).then(
+            text::int(10)
+                .padded()
+                .try_map(|int: String, span| match int.parse::<usize>() {
+                    Ok(i) if i > 0 => Ok(i - 1),
+                    _ => Err(Simple::custom(
+                        span,
+                        format!("the argument {} is not a positive index", int),
+                    )),
+                }),
+        ))
         .delimited_by(just('['), just(']'))
-        .map(|(base, int)| LogicFormula::BasisElem(base, int.parse::<usize>().unwrap() - 1));
+        .map(|(base, i)| LogicFormula::BasisElem(base, i));
 
         let truth = text::keyword("true").map(|_| LogicFormula::True);
         let falsehood = text::keyword("false").map(|_| LogicFormula::False);
@@ -79,28 +88,7 @@ pub fn symbolic_moves_parser<'a>(
         .separated_by(just(';'))
         .allow_trailing()
         .padded()
-        .map(|x| {
-            let basis_map = basis
-                .iter()
-                .enumerate()
-                .map(|(i, b)| (b.to_owned(), i))
-                .collect::<HashMap<String, usize>>();
-            let fun_map = fun_with_arities
-                .iter()
-                .enumerate()
-                .map(|(i, (f, _))| (f.to_owned(), i))
-                .collect::<HashMap<String, usize>>();
-            let mut formulas = vec![LogicFormula::False; basis_map.len() * fun_map.len()];
-            x.into_iter().for_each(|((b, f), l)| {
-                formulas[fun_map.get(&f).unwrap() * basis_map.len() + basis_map.get(&b).unwrap()] =
-                    l
-            });
-            SymbolicExistsMoves {
-                basis_map,
-                fun_map,
-                formulas,
-            }
-        });
+        .map(|x| x.into_iter().map(|((b, f), l)| (b, f, l)).collect());
 
     symbolic_move_list.then_ignore(end())
 }
@@ -0,0 +1,89 @@
+use std::collections::BTreeSet;
+
+use chumsky::prelude::*;
+
+use crate::ast::operator_table::OperatorTable;
+
+///
+/// Returns a parser for the following grammar:
+///
+// <Tables>  ::= <Entry> <Tables> | <Entry>
+// <Entry>   ::= <Op> `(' <Set> (`,' <Set>)* `)' `=' <Set> `;'
+//             | <Op> `(' `)' `=' <Set> `;'
+// <Set>     ::= `{' `}' | `{' <Id> (`,' <Id>)* `}'
+// <Op>      ::= ( a function in the arity file )
+// <Id>      ::= ( an element of the basis )
+///
+/// Each entry states that the operator maps the given sets of basis
+/// elements, one for each argument, to the set on the right-hand side. The
+/// number of sets must be the arity of the operator. The operators `and` and
+/// `or` cannot be given a table.
+///
+pub fn operators_parser<'a>(
+    fun_with_arities: &'a [(String, usize)],
+    basis: &'a [String],
+) -> impl Parser<char, Vec<OperatorTable>, Error = Simple<char>> + 'a {
+    let basis_parser = basis
+        .iter()
+        .map(|str| just(str.clone()).padded())
+        .collect::<Vec<_>>();
+
+    let set = choice(basis_parser)
+        .separated_by(just(','))
+        .delimited_by(just('{').padded(), just('}').padded())
+        .map(|x| x.into_iter().collect::<BTreeSet<_>>());
+
+    let fun_name = fun_with_arities
+        .iter()
+        .filter(|(f, _)| f != "and" && f != "or")
+        .map(|(f, arity)| {
+            just(f.clone())
+                .padded()
+                .then_ignore(just('('))
+                .to((f.clone(), *arity))
+        })
+        .collect::<Vec<_>>();
+
+    let entry = choice(fun_name)
+        .then(set.clone().separated_by(just(',')))
+        .then_ignore(just(')').padded())
+        .validate(|((f, arity), input), span, emit| {
+            if input.len() != arity {
+                emit(Simple::custom(
+                    span,
+                    format!(
+                        "operator {} has arity {}, but {} arguments are given",
+                        f,
+                        arity,
+                        input.len()
+                    ),
+                ))
+            }
+            (f, input)
+        })
+        .then_ignore(just('=').padded())
+        .then(set)
+        .then_ignore(just(';').padded());
+
+    entry.repeated().then_ignore(end()).map(move |entries| {
+        fun_with_arities
+            .iter()
+            .filter_map(|(f, arity)| {
+                let entries = entries
+                    .iter()
+                    .filter(|((g, _), _)| g == f)
+                    .map(|((_, input), output)| (input.clone(), output.clone()))
+                    .collect::<Vec<_>>();
+                if entries.is_empty() {
+                    None
+                } else {
+                    Some(OperatorTable {
+                        name: f.to_owned(),
+                        arity: *arity,
+                        entries,
+                    })
+                }
+            })
+            .collect()
+    })
+}
@@ -1,65 +1,221 @@
+pub mod certificate;
+pub mod checkpoint;
 mod play_data;
 pub mod player;
 pub mod position;
 mod position_counter_set;
+pub mod query;
+pub mod up_to;
 
 use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::hash::Hash;
 use std::ops::Deref;
 use std::rc::Rc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
 
 use crate::ast::fixpoint_system::{FixEq, FixType};
 use crate::ast::symbolic_moves_composed::{BasisElem, FormulaOperator, Node, SymbolicExistsMoves};
+use certificate::Certificate;
+use checkpoint::{Checkpoint, SaveCheckpoint};
 use play_data::PlayData;
 use player::Player;
 use position::{AdamPos, EvePos, Position};
 use position_counter_set::PositionCounterSet;
+use query::QueryError;
+use up_to::UpTo;
 
+#[derive(Serialize, Deserialize)]
 enum AltMoves {
-    Adam(Box<dyn Iterator<Item = Position>>),
+    /// The moves of the universal player which are still to be explored, in
+    /// reverse order.
+    Adam(Vec<EvePos>),
     Eve(Rc<Node<FormulaOperator>>),
 }
 
 type Playlist = Vec<(PlayData, (AltMoves, Rc<Counter>))>;
 type Counter = Vec<u32>;
 
-pub struct LocalAlgorithm<'a> {
+/// The next step of the local algorithm: either exploring a position, or
+/// backtracking after a player won the last position explored.
+#[derive(Clone, Serialize, Deserialize)]
+enum Step {
+    Explore(PlayData),
+    Backtrack(Player),
+    Done(Player),
+}
+
+/// The state of the local algorithm between two steps. Decisions and
+/// assumptions are ordered by a logical clock, which is incremented whenever
+/// one of them is taken.
+#[derive(Serialize, Deserialize)]
+struct State {
+    pl: Playlist,
+    assumptions: PositionCounterSet<u64>,
+    decisions: PositionCounterSet<u64>,
+    clock: u64,
+}
+
+impl State {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+}
+
+/// The local algorithm, over basis elements of type `B`.
+pub struct LocalAlgorithm<'a, B = String> {
     pub fix_system: &'a [FixEq],
-    pub symbolic_moves: &'a SymbolicExistsMoves,
+    pub symbolic_moves: &'a SymbolicExistsMoves<B>,
+    /// An optional up-to technique, which lets the existential player win
+    /// positions in the closure of the ones currently assumed
+    pub up_to: Option<&'a dyn UpTo<B>>,
 }
 
-impl<'a> LocalAlgorithm<'a> {
-    pub fn local_check(&self, b: String, i: usize) -> Player {
-        let m: usize = self.fix_system.len();
-        let c = Position::Eve(EvePos {
-            b: self.symbolic_moves.get_basis_usize(&b),
-            i,
-        });
-        self.explore(
-            PlayData {
-                pos: c,
-                k: Rc::new(vec![0; m]),
+impl<'a, B: Hash + Eq + Clone + Display> LocalAlgorithm<'a, B> {
+    /// Like `local_check`, but it also returns a certificate for the winner,
+    /// which can be validated independently with `Certificate::check`.
+    pub fn local_check_certified(
+        &self,
+        b: B,
+        i: usize,
+    ) -> Result<(Player, Certificate), QueryError> {
+        let pos = self.eve_pos(&b, i)?;
+        let (step, mut state) = self.start(pos.clone());
+        let winner = self.run(step, &mut state);
+        let certificate = Certificate::from_decisions(
+            self.symbolic_moves,
+            self.fix_system,
+            &winner,
+            pos,
+            state.decisions.get_p(&winner),
+        );
+        Ok((winner, certificate))
+    }
+}
+
+impl<'a, B: Hash + Eq + Clone> LocalAlgorithm<'a, B> {
+    /// Whether the basis element `b` is below the solution of the equation
+    /// with index `i`: it is if the existential player wins. Fails unless
+    /// `b` is in the basis of the equation.
+    pub fn local_check(&self, b: B, i: usize) -> Result<Player, QueryError> {
+        let (step, mut state) = self.start(self.eve_pos(&b, i)?);
+        Ok(self.run(step, &mut state))
+    }
+
+    /// Like `local_check`, but the state of the algorithm is handed to `save`
+    /// at least every `every`, so that the run can be resumed later on. The
+    /// run starts from `resume` if provided, which must have been saved for
+    /// the same query and the same `inputs`, that is a fingerprint of the
+    /// inputs the symbolic exists-moves were composed from.
+    pub fn local_check_checkpointed(
+        &self,
+        b: B,
+        i: usize,
+        inputs: &str,
+        resume: Option<Checkpoint>,
+        every: Duration,
+        save: &mut SaveCheckpoint,
+    ) -> Result<Player, Box<dyn std::error::Error>> {
+        let query = self.eve_pos(&b, i)?;
+        let mut checkpoint = match resume {
+            Some(checkpoint) => {
+                checkpoint.validate(inputs, &query)?;
+                checkpoint
+            }
+            None => {
+                let (step, state) = self.start(query.clone());
+                Checkpoint::new(inputs, query, step, state)
+            }
+        };
+
+        let mut last_save = Instant::now();
+        loop {
+            if let Step::Done(p) = &checkpoint.step {
+                return Ok(p.clone());
+            }
+            if last_save.elapsed() >= every {
+                save(&checkpoint)?;
+                last_save = Instant::now();
+            }
+            checkpoint.step = self.step(checkpoint.step.clone(), &mut checkpoint.state);
+        }
+    }
+
+    /// Fails unless there is an equation with index `i`.
+    fn equation(&self, i: usize) -> Result<(), QueryError> {
+        if i < self.fix_system.len() {
+            Ok(())
+        } else {
+            Err(QueryError::new(format!(
+                "there is no equation with index {}",
+                i
+            )))
+        }
+    }
+
+    /// The position of the existential player for the basis element `b` and
+    /// the equation with index `i`.
+    fn eve_pos(&self, b: &B, i: usize) -> Result<EvePos, QueryError> {
+        self.equation(i)?;
+        match self.symbolic_moves.basis_index(b) {
+            Some(b) if self.symbolic_moves.in_basis(b, i) => Ok(EvePos { b, i }),
+            _ => Err(QueryError::new(format!(
+                "the basis element is not in the basis of the equation {}",
+                i
+            ))),
+        }
+    }
+
+    fn start(&self, pos: EvePos) -> (Step, State) {
+        (
+            self.explore_from(Position::Eve(pos)),
+            State {
+                pl: vec![],
+                assumptions: PositionCounterSet::default(),
+                decisions: PositionCounterSet::default(),
+                clock: 0,
             },
-            vec![],
-            PositionCounterSet::default(),
-            PositionCounterSet::default(),
         )
     }
 
-    fn explore(
-        &self,
-        play_data: PlayData,
-        mut pl: Playlist,
-        mut assumptions: PositionCounterSet<Instant>,
-        mut decisions: PositionCounterSet<Instant>,
-    ) -> Player {
+    /// The first step of a play which starts from `pos`.
+    fn explore_from(&self, pos: Position) -> Step {
+        Step::Explore(PlayData {
+            pos,
+            k: Rc::new(vec![0; self.fix_system.len()]),
+        })
+    }
+
+    fn run(&self, mut step: Step, state: &mut State) -> Player {
+        loop {
+            step = match step {
+                Step::Done(p) => return p,
+                step => self.step(step, state),
+            }
+        }
+    }
+
+    fn step(&self, step: Step, state: &mut State) -> Step {
+        match step {
+            Step::Explore(play_data) => self.explore(play_data, state),
+            Step::Backtrack(p) => self.backtrack(p, state),
+            Step::Done(p) => Step::Done(p),
+        }
+    }
+
+    fn explore(&self, play_data: PlayData, state: &mut State) -> Step {
         if self.is_empty(&play_data) {
             let opponent = Player::get_opponent(&Position::get_controller(&play_data.pos));
-            decisions.insert(&opponent, play_data, Instant::now());
-            self.backtrack(opponent, pl, assumptions, decisions)
-        } else if let Some(p) = self.contains(&decisions, &play_data) {
-            self.backtrack(p, pl, assumptions, decisions)
-        } else if let Some((PlayData { k: kp, .. }, _)) = pl
+            let now = state.tick();
+            state.decisions.insert(&opponent, play_data, now);
+            Step::Backtrack(opponent)
+        } else if let Some(p) = self.contains(&state.decisions, &play_data) {
+            Step::Backtrack(p)
+        } else if let Some((PlayData { k: kp, .. }, _)) = state
+            .pl
             .iter()
             .find(|(PlayData { pos: cp, .. }, _)| cp == &play_data.pos)
         {
@@ -68,15 +224,13 @@ impl<'a> LocalAlgorithm<'a> {
                 // It is guaranteed that either kp < k for Eve or kp < k for Adam
                 false => Player::Adam,
             };
-            assumptions.insert(
-                &p,
-                PlayData {
-                    pos: play_data.pos,
-                    k: kp.clone(),
-                },
-                Instant::now(),
-            );
-            self.backtrack(p, pl, assumptions, decisions)
+            let assumption = PlayData {
+                pos: play_data.pos,
+                k: kp.clone(),
+            };
+            let now = state.tick();
+            state.assumptions.insert(&p, assumption, now);
+            Step::Backtrack(p)
         } else {
             let kp = Rc::new(Self::counter_next(
                 &play_data.k,
@@ -88,27 +242,30 @@ impl<'a> LocalAlgorithm<'a> {
                     let mut moves = Self::universal_move(x.clone());
 
                     let pp = PlayData {
-                        pos: moves.next().unwrap(),
+                        pos: Position::Eve(moves.pop().unwrap()),
                         k: kp.clone(),
                     };
-                    pl.push((play_data, (AltMoves::Adam(moves), kp)));
-                    self.explore(pp, pl, assumptions, decisions)
+                    state.pl.push((play_data, (AltMoves::Adam(moves), kp)));
+                    Step::Explore(pp)
                 }
                 Position::Eve(x @ EvePos { b, i }) => {
                     let f = self.symbolic_moves.get_formula(*b, *i);
+                    let now = state.tick();
                     let (new_formula, new_assumpt) =
-                        self.reduce(&f, &kp, &decisions, (x, &play_data.k), &pl);
-                    assumptions.union(new_assumpt);
+                        self.reduce(&f, &kp, &state.decisions, (x, &play_data.k), &state.pl, now);
+                    state.assumptions.union(new_assumpt);
                     if let Some(new_pos) = self.next_move(&new_formula) {
-                        pl.push((play_data, (AltMoves::Eve(new_formula), kp.clone())));
-                        let next_move = PlayData {
+                        state
+                            .pl
+                            .push((play_data, (AltMoves::Eve(new_formula), kp.clone())));
+                        Step::Explore(PlayData {
                             pos: Position::Adam(new_pos),
                             k: kp,
-                        };
-                        self.explore(next_move, pl, assumptions, decisions)
+                        })
                     } else {
-                        decisions.insert(&Player::Adam, play_data, Instant::now());
-                        self.backtrack(Player::Adam, pl, assumptions, decisions)
+                        let now = state.tick();
+                        state.decisions.insert(&Player::Adam, play_data, now);
+                        Step::Backtrack(Player::Adam)
                     }
                 }
             }
@@ -118,26 +275,23 @@ impl<'a> LocalAlgorithm<'a> {
     fn is_empty(&self, PlayData { pos: c, .. }: &PlayData) -> bool {
         match c {
             Position::Eve(EvePos { b, i }) => {
-                SymbolicExistsMoves::is_formula_false(&self.symbolic_moves.get_formula(*b, *i))
+                SymbolicExistsMoves::<B>::is_formula_false(&self.symbolic_moves.get_formula(*b, *i))
             }
             Position::Adam(AdamPos { x }) => x.iter().all(BTreeSet::is_empty),
         }
     }
 
-    fn backtrack(
-        &self,
-        p: Player,
-        mut pl: Playlist,
-        mut assumptions: PositionCounterSet<Instant>,
-        mut decisions: PositionCounterSet<Instant>,
-    ) -> Player {
-        if let Some((play_data, pi)) = pl.pop() {
+    fn backtrack(&self, p: Player, state: &mut State) -> Step {
+        if let Some((play_data, pi)) = state.pl.pop() {
             if let (Some(pos), Some(pip)) = match (&play_data.pos, pi) {
-                (_, (AltMoves::Adam(mut it), kp))
+                (_, (AltMoves::Adam(mut moves), kp))
                     if Position::get_controller(&play_data.pos) != p =>
                 {
-                    if let Some(pos) = it.next() {
-                        (Some(pos), Some((AltMoves::Adam(Box::new(it)), kp.clone())))
+                    if let Some(pos) = moves.pop() {
+                        (
+                            Some(Position::Eve(pos)),
+                            Some((AltMoves::Adam(moves), kp.clone())),
+                        )
                     } else {
                         (None, None)
                     }
@@ -145,9 +299,10 @@ impl<'a> LocalAlgorithm<'a> {
                 (Position::Eve(x), (AltMoves::Eve(f), kp))
                     if Position::get_controller(&play_data.pos) != p =>
                 {
+                    let now = state.tick();
                     let (fp, new_assumpt) =
-                        self.reduce(&f, &kp, &decisions, (&x, &play_data.k), &pl);
-                    assumptions.union(new_assumpt);
+                        self.reduce(&f, &kp, &state.decisions, (x, &play_data.k), &state.pl, now);
+                    state.assumptions.union(new_assumpt);
 
                     if let Some(new_pos) = self.next_move(&fp).map(Position::Adam) {
                         (Some(new_pos), Some((AltMoves::Eve(fp), kp.clone())))
@@ -158,49 +313,49 @@ impl<'a> LocalAlgorithm<'a> {
                 _ => (None, None),
             } {
                 let k = pip.1.clone();
-                pl.push((play_data, pip));
-                self.explore(PlayData { pos, k }, pl, assumptions, decisions)
+                state.pl.push((play_data, pip));
+                Step::Explore(PlayData { pos, k })
             } else {
-                let decision_time = Instant::now();
+                let decision_time = state.tick();
                 let opponent = Player::get_opponent(&p);
-                if let Some(after_not_valid) = assumptions.get_mut_p(&opponent).get_mut(&play_data)
+                if let Some(after_not_valid) =
+                    state.assumptions.get_mut_p(&opponent).get_mut(&play_data)
                 {
-                    Self::forget(&opponent, after_not_valid, &mut decisions);
-                    assumptions.get_mut_p(&opponent).remove(&play_data);
+                    Self::forget(&opponent, after_not_valid, &mut state.decisions);
+                    state.assumptions.get_mut_p(&opponent).remove(&play_data);
                 };
-                assumptions.get_mut_p(&p).remove(&play_data);
-                decisions.insert(&p, play_data, decision_time);
-                self.backtrack(p, pl, assumptions, decisions)
+                state.assumptions.get_mut_p(&p).remove(&play_data);
+                state.decisions.insert(&p, play_data, decision_time);
+                Step::Backtrack(p)
             }
         } else {
-            p
+            Step::Done(p)
         }
     }
 
     #[inline]
-    fn forget(
-        p: &Player,
-        after_not_valid: &mut Instant,
-        decisions: &mut PositionCounterSet<Instant>,
-    ) {
+    fn forget(p: &Player, after_not_valid: &mut u64, decisions: &mut PositionCounterSet<u64>) {
         decisions
             .get_mut_p(p)
             .retain(|_, inst| inst <= after_not_valid);
     }
 
+    /// Returns the moves of the universal player from `x`, in reverse order.
     #[inline]
-    fn universal_move(AdamPos { x }: AdamPos) -> Box<dyn Iterator<Item = Position>> {
-        Box::new(
-            x.into_iter()
-                .enumerate()
-                .flat_map(|(i, x_i)| x_i.into_iter().map(move |b| Position::Eve(EvePos { b, i }))),
-        )
+    fn universal_move(AdamPos { x }: AdamPos) -> Vec<EvePos> {
+        let mut moves = x
+            .into_iter()
+            .enumerate()
+            .flat_map(|(i, x_i)| x_i.into_iter().map(move |b| EvePos { b, i }))
+            .collect::<Vec<_>>();
+        moves.reverse();
+        moves
     }
 
     #[inline]
     pub fn contains(
         &self,
-        decisions: &PositionCounterSet<Instant>,
+        decisions: &PositionCounterSet<u64>,
         PlayData { pos: c, k }: &PlayData,
     ) -> Option<Player> {
         if decisions
@@ -290,11 +445,12 @@ impl<'a> LocalAlgorithm<'a> {
         &self,
         f: &Rc<Node<FormulaOperator>>,
         k: &Rc<Vec<u32>>,
-        decisions: &PositionCounterSet<Instant>,
+        decisions: &PositionCounterSet<u64>,
         last_move: (&EvePos, &Rc<Vec<u32>>),
         pl: &Playlist,
-    ) -> (Rc<Node<FormulaOperator>>, PositionCounterSet<Instant>) {
-        let (fp, new_assumpt) = self.apply_decisions(f, k, decisions, last_move, pl);
+        now: u64,
+    ) -> (Rc<Node<FormulaOperator>>, PositionCounterSet<u64>) {
+        let (fp, new_assumpt) = self.apply_decisions(f, k, decisions, last_move, pl, now);
         (self.symbolic_moves.simplify(fp), new_assumpt)
     }
 
@@ -302,10 +458,11 @@ impl<'a> LocalAlgorithm<'a> {
         &self,
         f: &Rc<Node<FormulaOperator>>,
         k: &Rc<Vec<u32>>,
-        decisions: &PositionCounterSet<Instant>,
+        decisions: &PositionCounterSet<u64>,
         (eve_pos, kp): (&EvePos, &Rc<Vec<u32>>),
         pl: &Playlist,
-    ) -> (Rc<Node<FormulaOperator>>, PositionCounterSet<Instant>) {
+        now: u64,
+    ) -> (Rc<Node<FormulaOperator>>, PositionCounterSet<u64>) {
         match f.deref() {
             Node {
                 val: FormulaOperator::Atom(BasisElem { b, i }),
@@ -334,7 +491,7 @@ impl<'a> LocalAlgorithm<'a> {
                     })
                 {
                     let mut new_assumpt = PositionCounterSet::default();
-                    new_assumpt.insert(&Player::Eve, play_data.clone(), Instant::now());
+                    new_assumpt.insert(&Player::Eve, play_data.clone(), now);
                     (self.symbolic_moves.get_true_atom(), new_assumpt)
                 } else if let Some((play_data, _)) =
                     pl.iter().find(|(PlayData { pos, k: kp }, _)| {
@@ -344,7 +501,7 @@ impl<'a> LocalAlgorithm<'a> {
                     })
                 {
                     let mut new_assumpt = PositionCounterSet::default();
-                    new_assumpt.insert(&Player::Adam, play_data.clone(), Instant::now());
+                    new_assumpt.insert(&Player::Adam, play_data.clone(), now);
                     (self.symbolic_moves.get_false_atom(), new_assumpt)
                 } else if &eve_pos.b == b && i == &eve_pos.i && self.counter_le_eve(k, kp) {
                     let mut new_assumpt = PositionCounterSet::default();
@@ -357,7 +514,7 @@ impl<'a> LocalAlgorithm<'a> {
                             }),
                             k: kp.clone(),
                         },
-                        Instant::now(),
+                        now,
                     );
                     (self.symbolic_moves.get_false_atom(), new_assumpt)
                 } else if &eve_pos.b == b && i == &eve_pos.i && self.counter_le_eve(kp, k) {
@@ -371,9 +528,15 @@ impl<'a> LocalAlgorithm<'a> {
                             }),
                             k: kp.clone(),
                         },
-                        Instant::now(),
+                        now,
                     );
                     (self.symbolic_moves.get_true_atom(), new_assumpt)
+                } else if let Some(assumed) = self.up_to_assumptions(*b, *i, k, (eve_pos, kp), pl) {
+                    let mut new_assumpt = PositionCounterSet::default();
+                    assumed
+                        .into_iter()
+                        .for_each(|play_data| new_assumpt.insert(&Player::Eve, play_data, now));
+                    (self.symbolic_moves.get_true_atom(), new_assumpt)
                 } else {
                     (f.clone(), PositionCounterSet::default())
                 }
@@ -386,7 +549,7 @@ impl<'a> LocalAlgorithm<'a> {
                 let mut new_formula_args = Vec::with_capacity(children.len());
                 for x_j in children {
                     let (new_formula_j, new_assumpts_j) =
-                        self.apply_decisions(x_j, k, decisions, (eve_pos, kp), pl);
+                        self.apply_decisions(x_j, k, decisions, (eve_pos, kp), pl, now);
                     new_assumpts.union(new_assumpts_j);
                     new_formula_args.push(new_formula_j);
                 }
@@ -401,6 +564,48 @@ impl<'a> LocalAlgorithm<'a> {
         }
     }
 
+    /// Returns the positions assumed to be won by the existential player from
+    /// which `(b, i)` follows by the up-to technique, if any: these are the
+    /// positions of the play for the same equation, whose counter is smaller
+    /// than `k` for the existential player.
+    fn up_to_assumptions(
+        &self,
+        b: usize,
+        i: usize,
+        k: &[u32],
+        (eve_pos, kp): (&EvePos, &Rc<Vec<u32>>),
+        pl: &Playlist,
+    ) -> Option<Vec<PlayData>> {
+        let up_to = self.up_to?;
+        let last_move = PlayData {
+            pos: Position::Eve(eve_pos.clone()),
+            k: kp.clone(),
+        };
+        let assumed = pl
+            .iter()
+            .map(|(play_data, _)| play_data)
+            .chain(std::iter::once(&last_move))
+            .filter(|PlayData { pos, k: kp }| {
+                matches!(pos, Position::Eve(EvePos { i: ip, .. }) if *ip == i
+                    && self.counter_le_eve(kp, k))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let names = assumed
+            .iter()
+            .filter_map(|PlayData { pos, .. }| match pos {
+                Position::Eve(EvePos { b, .. }) => Some(self.symbolic_moves.get_basis_elem(*b)),
+                Position::Adam(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        if !names.is_empty() && up_to.in_closure(self.symbolic_moves.get_basis_elem(b), i, &names) {
+            Some(assumed)
+        } else {
+            None
+        }
+    }
+
     #[inline]
     fn next_move(&self, f: &Rc<Node<FormulaOperator>>) -> Option<AdamPos> {
         match f.deref() {
@@ -0,0 +1,500 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap as HashMap;
+
+use super::play_data::PlayData;
+use super::player::Player;
+use super::position::{EvePos, Position};
+use crate::ast::fixpoint_system::{FixEq, FixType};
+use crate::ast::symbolic_moves_composed::{BasisElem, FormulaOperator, Node, SymbolicExistsMoves};
+use crate::zielonka::ParityArena;
+
+/// A position of the existential player, identified by the name of the basis
+/// element and by the index of the equation, starting from 0.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CertPos {
+    pub b: String,
+    pub i: usize,
+}
+
+/// Evidence for the result of the local algorithm: a positional strategy for
+/// the winner, restricted to the positions of the existential player it
+/// visits.
+///
+///  - If the winner is `Eve`, then for each position `(b, i)` the move is a
+///    set `X` of positions such that `X` satisfies `phi(b)(i)`,
+///  - if the winner is `Adam`, then for each position `(b, i)` the move is a
+///    set `T` of positions such that every set satisfying `phi(b)(i)`
+///    intersects `T`, so that the universal player can always answer with a
+///    position in `T`.
+///
+/// Every move must be contained in the set of positions of the certificate,
+/// and every cycle in the graph induced by the moves must be won by the
+/// winner, that is the greatest index of an equation along the cycle must be
+/// a greatest fixpoint for `Eve` and a least fixpoint for `Adam`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub winner: Player,
+    pub query: CertPos,
+    pub moves: Vec<(CertPos, Vec<CertPos>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CertificateError {
+    details: String,
+}
+
+impl CertificateError {
+    pub fn new(details: String) -> CertificateError {
+        CertificateError { details }
+    }
+}
+
+impl std::error::Error for CertificateError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid certificate: {}", self.details)
+    }
+}
+
+/// The sinks of the arena built by `Certificate::strategy`, which stand for
+/// the formulas `true` and `false`, respectively.
+const TRUE_NODE: usize = 0;
+const FALSE_NODE: usize = 1;
+
+type Strategy = HashMap<(usize, usize), BTreeSet<(usize, usize)>>;
+
+impl Certificate {
+    /// Builds a certificate for the winner of the local algorithm. The game is
+    /// first restricted to the positions decided for the winner, where any
+    /// other position is considered lost by the winner. If the query is not
+    /// won in the restricted game, the whole game reachable from the query is
+    /// considered instead.
+    pub(super) fn from_decisions<B: Hash + Eq + Clone + fmt::Display>(
+        moves: &SymbolicExistsMoves<B>,
+        fix_system: &[FixEq],
+        winner: &Player,
+        query: EvePos,
+        decisions: &HashMap<PlayData, u64>,
+    ) -> Certificate {
+        let query = (query.b, query.i);
+        let mut decided = decisions
+            .keys()
+            .filter_map(|PlayData { pos, .. }| match pos {
+                Position::Eve(EvePos { b, i }) => Some((*b, *i)),
+                Position::Adam(_) => None,
+            })
+            .collect::<BTreeSet<_>>();
+        decided.insert(query);
+
+        let strategy = Self::strategy(moves, fix_system, winner, &decided, query)
+            .or_else(|| {
+                let reachable = Self::reachable(query, |(b, i)| {
+                    let mut atoms = BTreeSet::new();
+                    Self::atoms(&moves.get_formula(b, i), &mut atoms);
+                    atoms
+                });
+                Self::strategy(moves, fix_system, winner, &reachable, query)
+            })
+            .unwrap_or_default();
+
+        let to_cert_pos = |(b, i): &(usize, usize)| CertPos {
+            b: moves.get_basis_elem(*b).to_string(),
+            i: *i,
+        };
+        let reachable =
+            Self::reachable(query, |pos| strategy.get(&pos).cloned().unwrap_or_default());
+        Certificate {
+            winner: winner.clone(),
+            query: to_cert_pos(&query),
+            moves: reachable
+                .iter()
+                .filter_map(|pos| {
+                    strategy
+                        .get(pos)
+                        .map(|x| (to_cert_pos(pos), x.iter().map(to_cert_pos).collect()))
+                })
+                .collect(),
+        }
+    }
+
+    /// Solves the game restricted to `positions` and returns the moves of the
+    /// winner, provided that it wins from `query`. The formula of each
+    /// position is unfolded into a parity game, where disjunctions are
+    /// controlled by the existential player and conjunctions by the universal
+    /// player.
+    fn strategy<B: Hash + Eq + Clone>(
+        moves: &SymbolicExistsMoves<B>,
+        fix_system: &[FixEq],
+        winner: &Player,
+        positions: &BTreeSet<(usize, usize)>,
+        query: (usize, usize),
+    ) -> Option<Strategy> {
+        let positions = positions.iter().copied().collect::<Vec<_>>();
+        let node = positions
+            .iter()
+            .enumerate()
+            .map(|(n, pos)| (*pos, n + 2))
+            .collect::<HashMap<_, _>>();
+        let is_pos = |n: usize| n >= 2 && n < positions.len() + 2;
+
+        let mut arena = Unfolding {
+            owner: vec![Player::Eve, Player::Adam],
+            priority: vec![0, 1],
+            succ: vec![vec![TRUE_NODE], vec![FALSE_NODE]],
+        };
+        for (_, i) in &positions {
+            let odd = fix_system[*i].fix_ty == FixType::Min;
+            arena.owner.push(Player::Eve);
+            arena.priority.push(2 * (i + 1) + odd as usize);
+            arena.succ.push(vec![]);
+        }
+        let outside = match winner {
+            Player::Eve => FALSE_NODE,
+            Player::Adam => TRUE_NODE,
+        };
+        for (pos, n) in &node {
+            let root = arena.unfold(&moves.get_formula(pos.0, pos.1), &node, outside);
+            arena.succ[*n] = vec![root];
+        }
+
+        let Unfolding {
+            owner,
+            priority,
+            succ,
+        } = arena;
+        let solution = ParityArena::new(owner.clone(), priority, succ.clone()).zielonka();
+        if &solution.winner[node[&query]] != winner {
+            return None;
+        }
+
+        // The positions reached from `n`, following the strategy of the winner
+        // and every choice of the opponent.
+        let collect = |n: usize| {
+            let mut x = BTreeSet::new();
+            let mut stack = vec![n];
+            while let Some(n) = stack.pop() {
+                if is_pos(n) {
+                    x.insert(positions[n - 2]);
+                } else if n == TRUE_NODE || n == FALSE_NODE {
+                    continue;
+                } else if &owner[n] == winner {
+                    stack.extend(solution.strategy[n]);
+                } else {
+                    stack.extend(succ[n].iter().copied());
+                }
+            }
+            x
+        };
+
+        Some(
+            node.iter()
+                .filter(|(_, n)| &solution.winner[**n] == winner)
+                .map(|(pos, n)| (*pos, collect(succ[*n][0])))
+                .collect(),
+        )
+    }
+
+    /// The positions reachable from `query`, where `next` returns the
+    /// successors of a position.
+    fn reachable(
+        query: (usize, usize),
+        next: impl Fn((usize, usize)) -> BTreeSet<(usize, usize)>,
+    ) -> BTreeSet<(usize, usize)> {
+        let mut reachable = BTreeSet::new();
+        let mut stack = vec![query];
+        while let Some(pos) = stack.pop() {
+            if reachable.insert(pos) {
+                stack.extend(next(pos));
+            }
+        }
+        reachable
+    }
+
+    fn atoms(f: &Node<FormulaOperator>, atoms: &mut BTreeSet<(usize, usize)>) {
+        match f {
+            Node {
+                val: FormulaOperator::Atom(BasisElem { b, i }),
+                ..
+            } => {
+                atoms.insert((*b, *i));
+            }
+            Node { children, .. } => children.iter().for_each(|c| Self::atoms(c, atoms)),
+        }
+    }
+
+    /// Validates the certificate against the system of fixpoint equations and
+    /// the composed symbolic exists-moves, without relying on the local
+    /// algorithm.
+    pub fn check(
+        &self,
+        fix_system: &[FixEq],
+        moves: &SymbolicExistsMoves,
+    ) -> Result<(), CertificateError> {
+        let index = |pos: &CertPos| match moves.basis_index(&pos.b) {
+            Some(b) if pos.i < fix_system.len() && moves.in_basis(b, pos.i) => Ok((b, pos.i)),
+            _ => Err(CertificateError::new(format!(
+                "{} is not a position of the game",
+                pos
+            ))),
+        };
+
+        let mut positions: HashMap<(usize, usize), usize> = HashMap::default();
+        for (pos, _) in &self.moves {
+            if positions.insert(index(pos)?, positions.len()).is_some() {
+                return Err(CertificateError::new(format!(
+                    "{} has more than one move",
+                    pos
+                )));
+            }
+        }
+        if !positions.contains_key(&index(&self.query)?) {
+            return Err(CertificateError::new(format!(
+                "there is no move for the query {}",
+                self.query
+            )));
+        }
+
+        let mut succ = Vec::with_capacity(self.moves.len());
+        for (pos, x) in &self.moves {
+            let (b, i) = index(pos)?;
+            let x = x
+                .iter()
+                .map(|p| match index(p)? {
+                    q if positions.contains_key(&q) => Ok(q),
+                    _ => Err(CertificateError::new(format!(
+                        "the move from {} leads to {}, which has no move",
+                        pos, p
+                    ))),
+                })
+                .collect::<Result<BTreeSet<_>, _>>()?;
+
+            let f = moves.get_formula(b, i);
+            let valid = match self.winner {
+                Player::Eve => Self::eval(&f, &|a| x.contains(&a)),
+                Player::Adam => !Self::eval(&f, &|a| !x.contains(&a)),
+            };
+            if !valid {
+                return Err(CertificateError::new(format!(
+                    "the move from {} is not allowed by phi({})({})",
+                    pos,
+                    pos.b,
+                    pos.i + 1
+                )));
+            }
+            succ.push(x.iter().map(|p| positions[p]).collect::<Vec<_>>());
+        }
+
+        let eq = self.moves.iter().map(|(pos, _)| pos.i).collect::<Vec<_>>();
+        let losing_ty = match self.winner {
+            Player::Eve => FixType::Min,
+            Player::Adam => FixType::Max,
+        };
+        let levels = eq
+            .iter()
+            .filter(|i| fix_system[**i].fix_ty == losing_ty)
+            .collect::<BTreeSet<_>>();
+        for l in levels {
+            let allowed = eq.iter().map(|i| i <= l).collect::<Vec<_>>();
+            let scc = Self::scc(&succ, &allowed);
+            if let Some(v) = (0..succ.len()).find(|v| {
+                eq[*v] == *l && succ[*v].iter().any(|w| allowed[*w] && scc[*w] == scc[*v])
+            }) {
+                return Err(CertificateError::new(format!(
+                    "there is a cycle through {} won by the {}",
+                    self.moves[v].0,
+                    Player::get_opponent(&self.winner)
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn eval(f: &Node<FormulaOperator>, val: &dyn Fn((usize, usize)) -> bool) -> bool {
+        match f {
+            Node {
+                val: FormulaOperator::Atom(BasisElem { b, i }),
+                ..
+            } => val((*b, *i)),
+            Node {
+                val: FormulaOperator::And,
+                children,
+            } => children.iter().all(|c| Self::eval(c, val)),
+            Node {
+                val: FormulaOperator::Or,
+                children,
+            } => children.iter().any(|c| Self::eval(c, val)),
+        }
+    }
+
+    /// Computes the strongly connected components of the graph restricted to
+    /// the `allowed` nodes, with an iterative version of Tarjan's algorithm.
+    /// Returns the component of each node, nodes which are not allowed belong
+    /// to no component.
+    fn scc(succ: &[Vec<usize>], allowed: &[bool]) -> Vec<usize> {
+        let n = succ.len();
+        let mut index = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut comp = vec![usize::MAX; n];
+        let mut stack = vec![];
+        let mut next = 0;
+        let mut n_comp = 0;
+
+        for root in (0..n).filter(|v| allowed[*v]) {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            let mut call = vec![(root, 0)];
+            while let Some((v, child)) = call.pop() {
+                if child == 0 {
+                    index[v] = next;
+                    low[v] = next;
+                    next += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                }
+                if let Some(&w) = succ[v].get(child) {
+                    call.push((v, child + 1));
+                    if !allowed[w] {
+                        continue;
+                    }
+                    if index[w] == usize::MAX {
+                        call.push((w, 0));
+                    } else if on_stack[w] {
+                        low[v] = low[v].min(index[w]);
+                    }
+                    continue;
+                }
+                if low[v] == index[v] {
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        comp[w] = n_comp;
+                        if w == v {
+                            break;
+                        }
+                    }
+                    n_comp += 1;
+                }
+                if let Some((u, _)) = call.last() {
+                    low[*u] = low[*u].min(low[v]);
+                }
+            }
+        }
+        comp
+    }
+}
+
+struct Unfolding {
+    owner: Vec<Player>,
+    priority: Vec<usize>,
+    succ: Vec<Vec<usize>>,
+}
+
+impl Unfolding {
+    fn unfold(
+        &mut self,
+        f: &Node<FormulaOperator>,
+        node: &HashMap<(usize, usize), usize>,
+        outside: usize,
+    ) -> usize {
+        match f {
+            Node {
+                val: FormulaOperator::Atom(BasisElem { b, i }),
+                ..
+            } => node.get(&(*b, *i)).copied().unwrap_or(outside),
+            Node {
+                val: FormulaOperator::And,
+                children,
+            } if children.is_empty() => TRUE_NODE,
+            Node {
+                val: FormulaOperator::Or,
+                children,
+            } if children.is_empty() => FALSE_NODE,
+            Node { val, children } => {
+                let children = children
+                    .iter()
+                    .map(|c| self.unfold(c, node, outside))
+                    .collect();
+                self.owner.push(match val {
+                    FormulaOperator::Or => Player::Eve,
+                    _ => Player::Adam,
+                });
+                self.priority.push(0);
+                self.succ.push(children);
+                self.succ.len() - 1
+            }
+        }
+    }
+}
+
+impl fmt::Display for CertPos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.b, self.i + 1)
+    }
+}
+
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "winner {};",
+            match self.winner {
+                Player::Eve => "eve",
+                Player::Adam => "adam",
+            }
+        )?;
+        writeln!(f, "query {};", self.query)?;
+        for (pos, x) in &self.moves {
+            let x = x.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+            writeln!(f, "{} -> {};", pos, x.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::algorithm::Player;
+    use crate::fixtures::Example;
+    use crate::parse;
+
+    /// Every certificate produced for the system in `tests/example_01` must be
+    /// valid, and must no longer be valid once its winner is swapped.
+    #[test]
+    fn certificates_example_01() {
+        let example = Example::load("example_01");
+        let algo = example.algorithm();
+        let Example {
+            fix_system,
+            basis,
+            moves,
+            ..
+        } = &example;
+
+        for b in basis {
+            for i in 0..fix_system.len() {
+                let (winner, mut certificate) =
+                    algo.local_check_certified(b.to_owned(), i).unwrap();
+                assert!(certificate.check(fix_system, moves).is_ok());
+
+                let printed = certificate.to_string();
+                assert_eq!(
+                    parse::parse_certificate(basis, printed).unwrap(),
+                    certificate
+                );
+
+                certificate.winner = Player::get_opponent(&winner);
+                assert!(certificate.check(fix_system, moves).is_err());
+            }
+        }
+    }
+}
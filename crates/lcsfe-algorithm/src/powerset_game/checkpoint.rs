@@ -0,0 +1,138 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::position::EvePos;
+use super::{State, Step};
+
+/// The version of the checkpoint format, bumped whenever the state of the
+/// local algorithm changes shape.
+const VERSION: u32 = 3;
+
+/// A snapshot of a run of the local algorithm, from which the run can be
+/// resumed. A checkpoint records the query it was taken for and a fingerprint
+/// of the inputs, so that it is never resumed against a different problem.
+#[derive(Serialize, Deserialize)]
+pub struct Checkpoint {
+    version: u32,
+    inputs: String,
+    query: EvePos,
+    pub(super) step: Step,
+    pub(super) state: State,
+}
+
+/// A callback which stores a checkpoint, e.g. to a file.
+pub type SaveCheckpoint<'a> = dyn FnMut(&Checkpoint) -> Result<(), Box<dyn std::error::Error>> + 'a;
+
+#[derive(Debug, Clone)]
+pub struct CheckpointError {
+    details: String,
+}
+
+impl CheckpointError {
+    fn new(msg: &str) -> CheckpointError {
+        CheckpointError {
+            details: msg.to_string(),
+        }
+    }
+}
+
+impl std::error::Error for CheckpointError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+impl fmt::Display for CheckpointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Cannot resume from checkpoint: {}", self.details)
+    }
+}
+
+impl Checkpoint {
+    pub(super) fn new(inputs: &str, query: EvePos, step: Step, state: State) -> Checkpoint {
+        Checkpoint {
+            version: VERSION,
+            inputs: inputs.to_owned(),
+            query,
+            step,
+            state,
+        }
+    }
+
+    /// Returns the fingerprint of the inputs the checkpoint was taken for.
+    pub fn inputs(&self) -> &str {
+        &self.inputs
+    }
+
+    pub(super) fn validate(&self, inputs: &str, query: &EvePos) -> Result<(), CheckpointError> {
+        if self.version != VERSION {
+            Err(CheckpointError::new(&format!(
+                "it was written in format version {}, while version {} is expected",
+                self.version, VERSION
+            )))
+        } else if self.inputs != inputs {
+            Err(CheckpointError::new(
+                "it was taken for different input files or options",
+            ))
+        } else if &self.query != query {
+            Err(CheckpointError::new("it was taken for a different query"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Checkpoint;
+    use crate::fixtures::Example;
+
+    /// A run resumed from any of its checkpoints must reach the same verdict
+    /// as an uninterrupted run, and a checkpoint must be refused for other
+    /// inputs.
+    #[test]
+    fn resume_example_01() {
+        let example = Example::load("example_01");
+        let algo = example.algorithm();
+
+        for b in &example.basis {
+            for i in 0..example.fix_system.len() {
+                let expected = algo.local_check(b.to_owned(), i).unwrap();
+                let mut saved = vec![];
+                let winner = algo
+                    .local_check_checkpointed(
+                        b.to_owned(),
+                        i,
+                        "42",
+                        None,
+                        Duration::ZERO,
+                        &mut |c| {
+                            saved.push(serde_json::to_string(c)?);
+                            Ok(())
+                        },
+                    )
+                    .unwrap();
+                assert_eq!(winner, expected);
+
+                for json in saved {
+                    let resume = |inputs: &str| {
+                        let checkpoint: Checkpoint = serde_json::from_str(&json).unwrap();
+                        algo.local_check_checkpointed(
+                            b.to_owned(),
+                            i,
+                            inputs,
+                            Some(checkpoint),
+                            Duration::MAX,
+                            &mut |_| Ok(()),
+                        )
+                    };
+                    assert_eq!(resume("42").unwrap(), expected);
+                    assert!(resume("43").is_err());
+                }
+            }
+        }
+    }
+}
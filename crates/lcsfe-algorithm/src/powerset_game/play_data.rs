@@ -1,8 +1,10 @@
 use std::rc::Rc;
 
+use serde::{Deserialize, Serialize};
+
 use super::position::Position;
 
-#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize)]
 pub struct PlayData {
     pub pos: Position,
     pub k: Rc<Vec<u32>>,
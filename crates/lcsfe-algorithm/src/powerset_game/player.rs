@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
-#[derive(PartialEq, Eq, Clone, Debug)]
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
 pub enum Player {
     Eve,
     Adam,
@@ -1,7 +1,9 @@
 use super::player::Player;
 use std::collections::BTreeSet;
 
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 pub enum Position {
     Eve(EvePos),
     Adam(AdamPos),
@@ -29,14 +31,14 @@ impl Position {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 
 pub struct EvePos {
     pub b: usize,
     pub i: usize,
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Serialize, Deserialize)]
 
 pub struct AdamPos {
     pub x: Vec<BTreeSet<usize>>,
@@ -1,10 +1,14 @@
 use super::play_data::PlayData;
 use super::player::Player;
 use rustc_hash::FxHashMap as HashMap;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "T: Serialize", deserialize = "T: Deserialize<'de>"))]
 pub struct PositionCounterSet<T> {
+    #[serde(with = "entries")]
     eve: HashMap<PlayData, T>,
+    #[serde(with = "entries")]
     adam: HashMap<PlayData, T>,
 }
 
@@ -40,8 +44,28 @@ impl<T> PositionCounterSet<T> {
             Player::Adam => &mut self.adam,
             Player::Eve => &mut self.eve,
         };
-        if let None = i.get(&k) {
+        if i.get(&k).is_none() {
             i.insert(k, value);
         }
     }
 }
+
+/// Maps are serialized as lists of entries, since their keys are not strings.
+mod entries {
+    use super::PlayData;
+    use rustc_hash::FxHashMap as HashMap;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        map: &HashMap<PlayData, T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<PlayData, T>, D::Error> {
+        Vec::<(PlayData, T)>::deserialize(deserializer).map(|v| v.into_iter().collect())
+    }
+}
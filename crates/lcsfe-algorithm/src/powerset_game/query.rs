@@ -0,0 +1,151 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::hash::Hash;
+
+use super::player::Player;
+use super::position::{AdamPos, EvePos, Position};
+use super::position_counter_set::PositionCounterSet;
+use super::{LocalAlgorithm, State};
+
+/// A query on the solution of the equation with index `i`, about the join of
+/// the basis elements `xs`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query<B = String> {
+    /// The join of `xs` is below the solution.
+    Below(Vec<B>, usize),
+    /// The solution is below the join of `xs`, which must be closed
+    /// downwards: every basis element of the equation which is below the
+    /// join must be in `xs`. Over a powerset lattice every set is.
+    Above(Vec<B>, usize),
+    /// The solution is the join of `xs`, which must be closed downwards.
+    Exact(Vec<B>, usize),
+}
+
+/// A query which cannot be answered, e.g. about a basis element which is not
+/// in the basis of the equation.
+#[derive(Debug, Clone)]
+pub struct QueryError {
+    details: String,
+}
+
+impl QueryError {
+    pub(super) fn new(details: String) -> QueryError {
+        QueryError { details }
+    }
+}
+
+impl std::error::Error for QueryError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid query: {}", self.details)
+    }
+}
+
+impl<'a, B: Hash + Eq + Clone> LocalAlgorithm<'a, B> {
+    /// Answers the query, the existential player wins if it holds. The
+    /// positions decided along the way are shared by all the basis elements
+    /// of the query: whether the join is below the solution is decided by a
+    /// single play from a position of the universal player, who chooses
+    /// among the elements, and the basis elements which are not below the
+    /// join are then checked one after the other, keeping the decisions of
+    /// the previous plays. Fails unless the basis elements are in the basis
+    /// of the equation.
+    pub fn check_query(&self, query: &Query<B>) -> Result<Player, QueryError> {
+        let mut state = State {
+            pl: vec![],
+            assumptions: PositionCounterSet::default(),
+            decisions: PositionCounterSet::default(),
+            clock: 0,
+        };
+        let winner = match query {
+            Query::Below(xs, i) => self.below(self.indices(xs, *i)?, *i, &mut state),
+            Query::Above(xs, i) => self.above(self.indices(xs, *i)?, *i, &mut state),
+            Query::Exact(xs, i) => {
+                let xs = self.indices(xs, *i)?;
+                match self.below(xs.clone(), *i, &mut state) {
+                    Player::Eve => self.above(xs, *i, &mut state),
+                    Player::Adam => Player::Adam,
+                }
+            }
+        };
+        Ok(winner)
+    }
+
+    fn below(&self, xs: BTreeSet<usize>, i: usize, state: &mut State) -> Player {
+        let mut x = vec![BTreeSet::new(); self.fix_system.len()];
+        x[i] = xs;
+        self.run_from(Position::Adam(AdamPos { x }), state)
+    }
+
+    fn above(&self, xs: BTreeSet<usize>, i: usize, state: &mut State) -> Player {
+        let outside = self
+            .symbolic_moves
+            .equation_basis(i)
+            .iter()
+            .filter(|b| !xs.contains(b));
+        for b in outside {
+            if self.run_from(Position::Eve(EvePos { b: *b, i }), state) == Player::Eve {
+                return Player::Adam;
+            }
+        }
+        Player::Eve
+    }
+
+    /// Runs the algorithm from `pos`, keeping the decisions of the previous
+    /// plays, which are sound once a play is over.
+    fn run_from(&self, pos: Position, state: &mut State) -> Player {
+        state.assumptions = PositionCounterSet::default();
+        let step = self.explore_from(pos);
+        self.run(step, state)
+    }
+
+    /// The indices of the basis elements `xs` of the equation with index `i`.
+    fn indices(&self, xs: &[B], i: usize) -> Result<BTreeSet<usize>, QueryError> {
+        self.equation(i)?;
+        xs.iter().map(|b| Ok(self.eve_pos(b, i)?.b)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Query;
+    use crate::algorithm::Player;
+    use crate::fixtures::Example;
+
+    /// In `tests/example_01` the solution of `x_1` is `{b}, {d}, {e}`, and
+    /// the queries must agree with the answers for single basis elements.
+    #[test]
+    fn queries_example_01() {
+        let example = Example::load("example_01");
+        let algo = example.algorithm();
+
+        let solution = example
+            .basis
+            .iter()
+            .filter(|b| algo.local_check(b.to_string(), 0).unwrap() == Player::Eve)
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut larger = solution.clone();
+        larger.push("{a}".to_string());
+        let smaller = solution[1..].to_vec();
+
+        let check = |query| algo.check_query(&query).unwrap();
+        assert_eq!(check(Query::Below(solution.clone(), 0)), Player::Eve);
+        assert_eq!(check(Query::Below(larger.clone(), 0)), Player::Adam);
+        assert_eq!(check(Query::Below(vec![], 0)), Player::Eve);
+        assert_eq!(check(Query::Above(larger.clone(), 0)), Player::Eve);
+        assert_eq!(check(Query::Above(smaller.clone(), 0)), Player::Adam);
+        assert_eq!(check(Query::Exact(solution, 0)), Player::Eve);
+        assert_eq!(check(Query::Exact(larger, 0)), Player::Adam);
+        assert_eq!(check(Query::Exact(smaller, 0)), Player::Adam);
+        assert!(algo
+            .check_query(&Query::Below(vec!["{f}".to_string()], 0))
+            .is_err());
+        assert!(algo.check_query(&Query::Above(vec![], 3)).is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Up-to techniques for the local algorithm. They are part of the library
+//! API only: a frontend which knows a sound closure for its equations, e.g.
+//! the equivalence of some states, sets `LocalAlgorithm::up_to`, while the
+//! commands of the CLI always run without one.
+
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap as HashMap;
+
+/// An up-to technique, that is a closure function on sets of basis elements
+/// which is a sound enhancement of the coinductive proof method, such as
+/// up-to equivalence or up-to congruence.
+///
+/// Whenever the local algorithm meets a position `(b, i)` which is neither
+/// decided nor on the current play, it is won by the existential player if
+/// `b` lies in the closure of the basis elements which are currently assumed
+/// for the same equation. It is the responsibility of the frontend to supply
+/// a closure which is sound for its system of fixpoint equations.
+pub trait UpTo<B = String> {
+    /// Returns whether the basis element `b` belongs to the closure of
+    /// `assumed`, for the equation with index `i`.
+    fn in_closure(&self, b: &B, i: usize, assumed: &[&B]) -> bool;
+}
+
+/// Up-to equivalence: a basis element is in the closure of a set whenever
+/// the set contains an element of its equivalence class.
+pub struct UpToEquivalence<B = String> {
+    class: HashMap<B, usize>,
+}
+
+impl<B: Hash + Eq> UpToEquivalence<B> {
+    /// Builds the technique from a partition of the basis, elements which are
+    /// in no class are equivalent only to themselves.
+    pub fn new(classes: Vec<Vec<B>>) -> UpToEquivalence<B> {
+        UpToEquivalence {
+            class: classes
+                .into_iter()
+                .enumerate()
+                .flat_map(|(n, class)| class.into_iter().map(move |b| (b, n)))
+                .collect(),
+        }
+    }
+}
+
+impl<B: Hash + Eq> UpTo<B> for UpToEquivalence<B> {
+    fn in_closure(&self, b: &B, _: usize, assumed: &[&B]) -> bool {
+        match self.class.get(b) {
+            Some(n) => assumed.iter().any(|bp| self.class.get(*bp) == Some(n)),
+            None => assumed.contains(&b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::{UpTo, UpToEquivalence};
+    use crate::algorithm::{LocalAlgorithm, Player};
+    use crate::ast::symbolic_moves_composed::SymbolicExistsMoves;
+    use crate::fixtures::Example;
+    use crate::parse;
+
+    /// Counts the positions won through the closure.
+    struct Counting<'a> {
+        up_to: &'a UpToEquivalence,
+        hits: Cell<usize>,
+    }
+
+    impl UpTo for Counting<'_> {
+        fn in_closure(&self, b: &String, i: usize, assumed: &[&String]) -> bool {
+            let hit = self.up_to.in_closure(b, i, assumed);
+            self.hits.set(self.hits.get() + hit as usize);
+            hit
+        }
+    }
+
+    /// The winner from `(b, i)` and the number of positions it decided.
+    fn explore(algo: &LocalAlgorithm, b: &str, i: usize) -> (Player, usize) {
+        let (step, mut state) = algo.start(algo.eve_pos(&b.to_string(), i).unwrap());
+        let winner = algo.run(step, &mut state);
+        let decided = [Player::Eve, Player::Adam]
+            .iter()
+            .map(|p| state.decisions.get_p(p).len())
+            .sum();
+        (winner, decided)
+    }
+
+    /// In `tests/example_01` the states `{d}` and `{e}` are bisimilar, thus
+    /// up-to bisimilarity must not change the solution of the system.
+    #[test]
+    fn up_to_bisimilarity_example_01() {
+        let example = Example::load("example_01");
+        let bisimilarity = UpToEquivalence::new(vec![vec!["{d}".to_string(), "{e}".to_string()]]);
+        let counting = Counting {
+            up_to: &bisimilarity,
+            hits: Cell::new(0),
+        };
+        let algo = example.algorithm();
+        let algo_up_to = LocalAlgorithm {
+            up_to: Some(&counting),
+            ..example.algorithm()
+        };
+
+        for b in &example.basis {
+            for i in 0..example.fix_system.len() {
+                let (winner, decided) = explore(&algo, b, i);
+                let (winner_up_to, decided_up_to) = explore(&algo_up_to, b, i);
+                assert_eq!(winner, winner_up_to, "{}, {}", b, i + 1);
+                assert!(decided_up_to <= decided, "{}, {}", b, i + 1);
+            }
+        }
+    }
+
+    /// On a cycle of two bisimilar states, the position of the second state
+    /// follows from the assumption on the first one, thus it is never
+    /// explored.
+    #[test]
+    fn up_to_bisimilarity_prunes_cycle() {
+        let arity = parse::parse_fun_arity("box 1".to_string()).unwrap();
+        let fix_system =
+            parse::parse_fixpoint_system(&arity, "x =max box(x);".to_string()).unwrap();
+        let basis = parse::parse_basis("{a}\n{b}".to_string()).unwrap();
+        let moves = parse::parse_symbolic_system(
+            &arity,
+            &basis,
+            "phi({a})(box) = [{b}, 1];\nphi({b})(box) = [{a}, 1];".to_string(),
+        )
+        .unwrap();
+        let moves = SymbolicExistsMoves::compose(&fix_system, &moves, &basis).unwrap();
+        let bisimilarity = UpToEquivalence::new(vec![basis.clone()]);
+        let counting = Counting {
+            up_to: &bisimilarity,
+            hits: Cell::new(0),
+        };
+        let algo = LocalAlgorithm {
+            fix_system: &fix_system,
+            symbolic_moves: &moves,
+            up_to: None,
+        };
+        let algo_up_to = LocalAlgorithm {
+            fix_system: &fix_system,
+            symbolic_moves: &moves,
+            up_to: Some(&counting),
+        };
+
+        let (winner, decided) = explore(&algo, "{a}", 0);
+        let (winner_up_to, decided_up_to) = explore(&algo_up_to, "{a}", 0);
+        assert_eq!(winner, Player::Eve);
+        assert_eq!(winner_up_to, Player::Eve);
+        assert_eq!(counting.hits.get(), 1);
+        assert!(decided_up_to < decided);
+    }
+}
@@ -0,0 +1,712 @@
+//! A verification problem for the local algorithm: the operators, a system of
+//! fixpoint equations, its basis and the symbolic exists-moves, each of which
+//! is either parsed or constructed in code. The problem is validated, the
+//! system normalized if requested, and the moves composed with it once, after
+//! which the variables of the original system can be queried by name.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use rustc_hash::FxHashMap as HashMap;
+
+use crate::ast::fixpoint_system::{ExpFixEq, FixEq};
+use crate::ast::lattice::{Lattice, LatticeError};
+use crate::ast::operator_table::{derive_symbolic_moves, OperatorTable};
+use crate::ast::symbolic_moves::{LogicFormula, SymbolicExistsMoves as UncomposedMoves};
+use crate::ast::symbolic_moves_composed::{ComposeError, SymbolicExistsMoves};
+use crate::normalizer::normalize_system;
+use crate::parser::{self, ParserError};
+use crate::powerset_game::player::Player;
+use crate::powerset_game::query::QueryError;
+use crate::powerset_game::LocalAlgorithm;
+use crate::validation::{
+    required_moves, validate_fix_system, validate_moves, Validation, ValidationError,
+};
+
+#[derive(Debug)]
+pub enum ProblemError {
+    /// A part of the problem was neither parsed nor constructed, e.g. a basis
+    /// declared by an equation
+    Missing(String),
+    Parse(ParserError),
+    Validation(ValidationError),
+    Compose(ComposeError),
+    Lattice(LatticeError),
+    /// The variable or the basis element queried is not in the problem
+    Query(String),
+}
+
+impl std::error::Error for ProblemError {}
+
+impl fmt::Display for ProblemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProblemError::Parse(e) => write!(f, "{}", e),
+            ProblemError::Validation(e) => write!(f, "{}", e),
+            ProblemError::Compose(e) => write!(f, "{}", e),
+            ProblemError::Lattice(e) => write!(f, "{}", e),
+            ProblemError::Missing(details) | ProblemError::Query(details) => {
+                write!(f, "{}", details)
+            }
+        }
+    }
+}
+
+impl From<ParserError> for ProblemError {
+    fn from(e: ParserError) -> Self {
+        ProblemError::Parse(e)
+    }
+}
+
+impl From<ValidationError> for ProblemError {
+    fn from(e: ValidationError) -> Self {
+        ProblemError::Validation(e)
+    }
+}
+
+impl From<LatticeError> for ProblemError {
+    fn from(e: LatticeError) -> Self {
+        ProblemError::Lattice(e)
+    }
+}
+
+impl From<ComposeError> for ProblemError {
+    fn from(e: ComposeError) -> Self {
+        ProblemError::Compose(e)
+    }
+}
+
+impl From<QueryError> for ProblemError {
+    fn from(e: QueryError) -> Self {
+        ProblemError::Query(e.to_string())
+    }
+}
+
+/// A part of the problem, as a source to parse or as a value.
+enum Input<T> {
+    Source(String),
+    Value(T),
+}
+
+/// A basis, either as a list of elements or as a finite lattice whose
+/// join-irreducible elements are the basis.
+enum BasisInput {
+    Source(String),
+    Value(Vec<String>),
+    LatticeSource(String),
+    Lattice(Lattice),
+}
+
+/// The symbolic exists-moves, either listed or derived from the tables of
+/// the operators.
+enum MovesInput {
+    Source(String),
+    Value(MovesList),
+    TablesSource(String),
+    Tables(Vec<OperatorTable>),
+}
+
+type Arity = Vec<(String, usize)>;
+type MovesList = Vec<(String, String, LogicFormula)>;
+
+/// Collects the parts of a `Problem`, in any order, since they are parsed
+/// only when the problem is built: the equations and the moves are parsed
+/// against the operators, and the moves against the bases as well.
+#[derive(Default)]
+pub struct ProblemBuilder {
+    operators: Option<Input<Arity>>,
+    equations: Option<Input<Vec<FixEq>>>,
+    basis: Option<BasisInput>,
+    named: Vec<(String, BasisInput)>,
+    moves: Option<MovesInput>,
+    normalize: bool,
+    strict: bool,
+}
+
+impl ProblemBuilder {
+    /// The operators, each with its arity.
+    pub fn operators(mut self, arity: Vec<(String, usize)>) -> ProblemBuilder {
+        self.operators = Some(Input::Value(arity));
+        self
+    }
+
+    pub fn parse_operators(mut self, src: impl Into<String>) -> ProblemBuilder {
+        self.operators = Some(Input::Source(src.into()));
+        self
+    }
+
+    /// The system of fixpoint equations, where the first equation is the
+    /// outermost one.
+    pub fn equations(mut self, fix_system: Vec<FixEq>) -> ProblemBuilder {
+        self.equations = Some(Input::Value(fix_system));
+        self
+    }
+
+    pub fn parse_equations(mut self, src: impl Into<String>) -> ProblemBuilder {
+        self.equations = Some(Input::Source(src.into()));
+        self
+    }
+
+    /// The basis of the equations which do not declare one by name.
+    pub fn basis(mut self, basis: Vec<String>) -> ProblemBuilder {
+        self.basis = Some(BasisInput::Value(basis));
+        self
+    }
+
+    pub fn parse_basis(mut self, src: impl Into<String>) -> ProblemBuilder {
+        self.basis = Some(BasisInput::Source(src.into()));
+        self
+    }
+
+    /// As `basis`, with the lattice whose join-irreducible elements are the
+    /// basis. The moves are validated against its order, and the queries
+    /// may be about any of its elements, see `Problem::elements`.
+    pub fn lattice(mut self, lattice: Lattice) -> ProblemBuilder {
+        self.basis = Some(BasisInput::Lattice(lattice));
+        self
+    }
+
+    pub fn parse_lattice(mut self, src: impl Into<String>) -> ProblemBuilder {
+        self.basis = Some(BasisInput::LatticeSource(src.into()));
+        self
+    }
+
+    /// The basis of the equations which declare it by name, as in
+    /// `x : NAME =max ...`.
+    pub fn named_basis(mut self, name: impl Into<String>, basis: Vec<String>) -> ProblemBuilder {
+        self.named.push((name.into(), BasisInput::Value(basis)));
+        self
+    }
+
+    pub fn parse_named_basis(
+        mut self,
+        name: impl Into<String>,
+        src: impl Into<String>,
+    ) -> ProblemBuilder {
+        self.named
+            .push((name.into(), BasisInput::Source(src.into())));
+        self
+    }
+
+    /// As `named_basis`, with a lattice, see `lattice`.
+    pub fn named_lattice(mut self, name: impl Into<String>, lattice: Lattice) -> ProblemBuilder {
+        self.named.push((name.into(), BasisInput::Lattice(lattice)));
+        self
+    }
+
+    pub fn parse_named_lattice(
+        mut self,
+        name: impl Into<String>,
+        src: impl Into<String>,
+    ) -> ProblemBuilder {
+        self.named
+            .push((name.into(), BasisInput::LatticeSource(src.into())));
+        self
+    }
+
+    /// The symbolic exists-moves, listed as triples `(b, f, formula)`. The
+    /// moves which are not listed are `false`.
+    pub fn moves(mut self, moves: Vec<(String, String, LogicFormula)>) -> ProblemBuilder {
+        self.moves = Some(MovesInput::Value(moves));
+        self
+    }
+
+    pub fn parse_moves(mut self, src: impl Into<String>) -> ProblemBuilder {
+        self.moves = Some(MovesInput::Source(src.into()));
+        self
+    }
+
+    /// The tables of the operators over the basis, from which the symbolic
+    /// exists-moves are derived in place of `moves`.
+    pub fn operator_tables(mut self, tables: Vec<OperatorTable>) -> ProblemBuilder {
+        self.moves = Some(MovesInput::Tables(tables));
+        self
+    }
+
+    pub fn parse_operator_tables(mut self, src: impl Into<String>) -> ProblemBuilder {
+        self.moves = Some(MovesInput::TablesSource(src.into()));
+        self
+    }
+
+    /// Whether the system is normalized before it is solved, by default not.
+    pub fn normalize(mut self, normalize: bool) -> ProblemBuilder {
+        self.normalize = normalize;
+        self
+    }
+
+    /// Whether the warnings of the validation are errors, by default not.
+    pub fn strict(mut self, strict: bool) -> ProblemBuilder {
+        self.strict = strict;
+        self
+    }
+
+    /// Parses, validates and composes the problem.
+    pub fn build(self) -> Result<Problem, ProblemError> {
+        let arity = match self.operators.ok_or_else(|| missing("operators"))? {
+            Input::Source(src) => parser::parse_fun_arity(src)?,
+            Input::Value(arity) => arity,
+        };
+        let original = match self.equations.ok_or_else(|| missing("equations"))? {
+            Input::Source(src) => parser::parse_fixpoint_system(&arity, src)?,
+            Input::Value(fix_system) => fix_system,
+        };
+        let mut warnings = validate_fix_system(&arity, &original).into_result(self.strict)?;
+
+        let mut lattices = HashMap::default();
+        let (default, lattice) = read_basis(self.basis.ok_or_else(|| missing("basis"))?)?;
+        lattices.extend(lattice.map(|lattice| (None, lattice)));
+        // The moves range over the union of the bases, in the order given
+        let mut basis = default.clone();
+        let mut named = HashMap::default();
+        for (name, named_basis) in self.named {
+            let (named_basis, lattice) = read_basis(named_basis)?;
+            for b in &named_basis {
+                if !basis.contains(b) {
+                    basis.push(b.to_owned());
+                }
+            }
+            lattices.extend(lattice.map(|lattice| (Some(name.to_owned()), lattice)));
+            named.insert(name, named_basis);
+        }
+        // The tables give the moves over a single basis, ordered as a powerset
+        if matches!(
+            self.moves,
+            Some(MovesInput::TablesSource(_) | MovesInput::Tables(_))
+        ) && (!lattices.is_empty() || !named.is_empty())
+        {
+            Validation {
+                errors: vec![
+                    "the tables of the operators cannot be combined with lattices or named bases"
+                        .to_string(),
+                ],
+                warnings: vec![],
+            }
+            .into_result(self.strict)?;
+        }
+        let bases = Bases { default, named };
+
+        let list = |moves: MovesList| -> Result<_, ProblemError> {
+            let eq_bases = original
+                .iter()
+                .map(|eq| bases.of(eq))
+                .collect::<Result<Vec<_>, _>>()?;
+            let required = required_moves(&original, &eq_bases);
+            let validation = validate_moves(&arity, &basis, &required, &moves);
+            Ok((
+                UncomposedMoves::from_list(&arity, &basis, moves),
+                validation.into_result(self.strict)?,
+            ))
+        };
+        // The moves derived from the tables are complete by construction
+        let (moves, moves_warnings) = match self.moves.ok_or_else(|| missing("moves"))? {
+            MovesInput::Source(src) => list(parser::parse_symbolic_list(&arity, &basis, src)?)?,
+            MovesInput::Value(moves) => list(moves)?,
+            MovesInput::TablesSource(src) => {
+                let tables = parser::parse_operators(&arity, &basis, src)?;
+                (derive_symbolic_moves(&arity, &basis, &tables), vec![])
+            }
+            MovesInput::Tables(tables) => (derive_symbolic_moves(&arity, &basis, &tables), vec![]),
+        };
+        warnings.extend(moves_warnings);
+        for (name, lattice) in &lattices {
+            lattice.validate_moves_over(&moves, &arguments_over(&original, name))?;
+        }
+
+        let composed = compose(&original, &bases, &moves, self.normalize)?;
+        Ok(Problem {
+            original,
+            bases,
+            lattices,
+            uncomposed: moves,
+            normalize: self.normalize,
+            composed,
+            warnings,
+        })
+    }
+}
+
+fn missing(what: &str) -> ProblemError {
+    ProblemError::Missing(format!("The {} of the problem are not given", what))
+}
+
+/// For each operator applied in the equations over the basis `name`, whether
+/// each of its arguments ranges over that basis wherever it is applied: a
+/// variable ranges over the basis of its equation, and any other expression
+/// over the basis of the equation it occurs in.
+fn arguments_over(original: &[FixEq], name: &Option<String>) -> HashMap<String, Vec<bool>> {
+    let basis_of = original
+        .iter()
+        .map(|eq| (eq.var.as_str(), &eq.basis))
+        .collect::<HashMap<_, _>>();
+    let mut args: HashMap<String, Vec<bool>> = HashMap::default();
+    for eq in original.iter().filter(|eq| &eq.basis == name) {
+        eq.exp.visit(&mut |exp| {
+            if let ExpFixEq::Operator(op, xs) = exp {
+                let over = args
+                    .entry(op.to_owned())
+                    .or_insert_with(|| vec![true; xs.len()]);
+                for (over, x) in over.iter_mut().zip(xs) {
+                    *over &= match x {
+                        ExpFixEq::Id(var) => basis_of.get(var.as_str()) == Some(&name),
+                        _ => true,
+                    };
+                }
+            }
+        });
+    }
+    args
+}
+
+/// Reads the basis, along with its lattice if it is given as one.
+fn read_basis(basis: BasisInput) -> Result<(Vec<String>, Option<Lattice>), ProblemError> {
+    match basis {
+        BasisInput::Source(src) => {
+            let basis = parser::parse_basis(src).map_err(|errs| {
+                if errs.is_empty() {
+                    ParserError::new("Invalid basis".to_string())
+                } else {
+                    ParserError::merge(errs)
+                }
+            })?;
+            Ok((basis, None))
+        }
+        BasisInput::Value(basis) => Ok((basis, None)),
+        BasisInput::LatticeSource(src) => {
+            let lattice = parser::parse_lattice(src)?;
+            Ok((lattice.basis(), Some(lattice)))
+        }
+        BasisInput::Lattice(lattice) => Ok((lattice.basis(), Some(lattice))),
+    }
+}
+
+/// The default basis and the named ones.
+struct Bases {
+    default: Vec<String>,
+    named: HashMap<String, Vec<String>>,
+}
+
+impl Bases {
+    /// The basis of the equation, the named one it declares, if any.
+    fn of(&self, eq: &FixEq) -> Result<Vec<String>, ProblemError> {
+        match &eq.basis {
+            Some(name) => self.named.get(name).cloned().ok_or_else(|| {
+                ProblemError::Missing(format!(
+                    "The basis {} of the variable {} is not given",
+                    name, eq.var
+                ))
+            }),
+            None => Ok(self.default.to_vec()),
+        }
+    }
+}
+
+/// A problem ready to be solved, see `ProblemBuilder`.
+pub struct Problem {
+    original: Vec<FixEq>,
+    bases: Bases,
+    /// The lattice of each basis, by name, the default one as `None`
+    lattices: HashMap<Option<String>, Lattice>,
+    uncomposed: UncomposedMoves,
+    normalize: bool,
+    composed: Composed,
+    warnings: Vec<String>,
+}
+
+/// The system which is solved, normalized if requested, along with the map
+/// from the original variables to those of the normalized system, the
+/// symbolic exists-moves composed with it, and the time it took.
+struct Composed {
+    fix_system: Vec<FixEq>,
+    var_map: HashMap<String, String>,
+    moves: SymbolicExistsMoves,
+    time: Duration,
+}
+
+fn compose(
+    original: &[FixEq],
+    bases: &Bases,
+    moves: &UncomposedMoves,
+    normalize: bool,
+) -> Result<Composed, ProblemError> {
+    let start = Instant::now();
+    let (fix_system, var_map) = if normalize {
+        normalize_system(original.to_vec())
+    } else {
+        (original.to_vec(), HashMap::default())
+    };
+    let eq_bases = fix_system
+        .iter()
+        .map(|eq| bases.of(eq))
+        .collect::<Result<Vec<_>, _>>()?;
+    let moves = SymbolicExistsMoves::compose_with_bases(&fix_system, moves, &eq_bases)?;
+    Ok(Composed {
+        fix_system,
+        var_map,
+        moves,
+        time: start.elapsed(),
+    })
+}
+
+impl Problem {
+    pub fn builder() -> ProblemBuilder {
+        ProblemBuilder::default()
+    }
+
+    /// Switches the normalization of the system, composing the moves again
+    /// if it changes.
+    pub fn set_normalize(&mut self, normalize: bool) -> Result<(), ProblemError> {
+        if self.normalize != normalize {
+            self.composed = compose(&self.original, &self.bases, &self.uncomposed, normalize)?;
+            self.normalize = normalize;
+        }
+        Ok(())
+    }
+
+    /// Whether `basis_elem` is below the solution of the variable `var_name`
+    /// of the original system: it is if the existential player wins.
+    pub fn check(&self, var_name: &str, basis_elem: &str) -> Result<Player, ProblemError> {
+        let i = self.index(var_name)?;
+        self.check_basis(var_name, i, &[basis_elem.to_owned()])?;
+        Ok(self.algorithm().local_check(basis_elem.to_owned(), i)?)
+    }
+
+    /// Fails unless the basis elements belong to the basis of the variable
+    /// `var_name`, whose index in `fix_system` is `i`.
+    pub fn check_basis(
+        &self,
+        var_name: &str,
+        i: usize,
+        elements: &[String],
+    ) -> Result<(), ProblemError> {
+        for x in elements {
+            match self.composed.moves.basis_index(x) {
+                Some(b) if self.composed.moves.in_basis(b, i) => {}
+                _ => {
+                    return Err(ProblemError::Query(format!(
+                        "{} is not in the basis of the variable {}",
+                        x, var_name
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The index in `fix_system` of the variable `var_name` of the original
+    /// system.
+    pub fn index(&self, var_name: &str) -> Result<usize, ProblemError> {
+        let not_found = || ProblemError::Query(format!("Cannot find variable {}", var_name));
+        // The map has the variables introduced by normalization as well
+        if !self.original.iter().any(|eq| eq.var == var_name) {
+            return Err(not_found());
+        }
+        let var = self
+            .composed
+            .var_map
+            .get(var_name)
+            .map_or(var_name, String::as_str);
+        self.composed
+            .fix_system
+            .iter()
+            .position(|eq| eq.var == var)
+            .ok_or_else(not_found)
+    }
+
+    /// The basis elements which the join of `xs` stands for in the basis of
+    /// the variable `var_name` of the original system: over a lattice those
+    /// below the join, otherwise `xs` themselves, sorted.
+    pub fn elements(&self, var_name: &str, xs: &[String]) -> Result<Vec<String>, ProblemError> {
+        let eq = self
+            .original
+            .iter()
+            .find(|eq| eq.var == var_name)
+            .ok_or_else(|| ProblemError::Query(format!("Cannot find variable {}", var_name)))?;
+        let mut elements = match self.lattices.get(&eq.basis) {
+            Some(lattice) => lattice
+                .join_all(xs)
+                .and_then(|join| lattice.basis_below(&join))
+                .ok_or_else(|| {
+                    ProblemError::Query(format!(
+                        "{} is not an element of the lattice of {}",
+                        xs.iter()
+                            .find(|x| lattice.index(x).is_none())
+                            .map_or("", |x| x.as_str()),
+                        eq.var
+                    ))
+                })?,
+            None => xs.to_vec(),
+        };
+        elements.sort();
+        elements.dedup();
+        Ok(elements)
+    }
+
+    /// The local algorithm on the problem, e.g. to ask other queries or to
+    /// produce a certificate.
+    pub fn algorithm(&self) -> LocalAlgorithm<'_> {
+        LocalAlgorithm {
+            fix_system: &self.composed.fix_system,
+            symbolic_moves: &self.composed.moves,
+            up_to: None,
+        }
+    }
+
+    /// The system as given, before normalization.
+    pub fn original(&self) -> &[FixEq] {
+        &self.original
+    }
+
+    /// The system which is solved, normalized if requested.
+    pub fn fix_system(&self) -> &[FixEq] {
+        &self.composed.fix_system
+    }
+
+    /// The map from the original variables to those of the normalized system,
+    /// empty if the system is not normalized.
+    pub fn var_map(&self) -> &HashMap<String, String> {
+        &self.composed.var_map
+    }
+
+    pub fn moves(&self) -> &SymbolicExistsMoves {
+        &self.composed.moves
+    }
+
+    /// The time it took to normalize the system and compose the moves.
+    pub fn compose_time(&self) -> Duration {
+        self.composed.time
+    }
+
+    /// The warnings of the validation, when it is not strict.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Problem, ProblemError};
+    use crate::algorithm::Player;
+    use crate::fixtures;
+
+    /// In `tests/example_01` the solution of `x_1` is `{b}, {d}, {e}`, and
+    /// the one of `x_2` every element but `{c}`, with and without
+    /// normalization, which renames the variables.
+    #[test]
+    fn problem_example_01() {
+        let src = |f| fixtures::src("example_01", f);
+        for normalize in [false, true] {
+            let problem = Problem::builder()
+                .parse_moves(src("symbolic"))
+                .parse_basis(src("basis"))
+                .parse_equations(src("fix_system"))
+                .parse_operators(src("arity"))
+                .normalize(normalize)
+                .build()
+                .unwrap();
+            for b in ["{a}", "{b}", "{c}", "{d}", "{e}"] {
+                let winner = |holds: bool| if holds { Player::Eve } else { Player::Adam };
+                assert_eq!(
+                    problem.check("x_1", b).unwrap(),
+                    winner(!["{a}", "{c}"].contains(&b))
+                );
+                assert_eq!(problem.check("x_2", b).unwrap(), winner(b != "{c}"));
+            }
+            assert!(problem.check("x_3", "{a}").is_err());
+            assert!(problem.check("x_1", "{f}").is_err());
+        }
+    }
+
+    /// The moves may be derived from the tables of the operators and the
+    /// basis given as a lattice, whose elements stand for the basis elements
+    /// below them, and normalization may be switched on a built problem.
+    #[test]
+    fn problem_lattice_and_tables() {
+        let src = |f| fixtures::src("example_chain", f);
+        let mut problem = Problem::builder()
+            .parse_operators(src("arity"))
+            .parse_equations(src("fix_system"))
+            .parse_lattice(src("lattice"))
+            .parse_moves(src("symbolic"))
+            .build()
+            .unwrap();
+        assert_eq!(
+            problem.elements("x", &["2".to_string()]).unwrap(),
+            vec!["1", "2"]
+        );
+        assert!(problem.elements("x", &["4".to_string()]).is_err());
+        for normalize in [true, false] {
+            problem.set_normalize(normalize).unwrap();
+            assert_eq!(problem.var_map().is_empty(), !normalize);
+            assert_eq!(problem.check("x", "3").unwrap(), Player::Eve);
+        }
+
+        let src = |f| fixtures::src("example_01", f);
+        let problem = Problem::builder()
+            .parse_operators(src("arity"))
+            .parse_equations(src("fix_system"))
+            .parse_basis(src("basis"))
+            .parse_operator_tables(src("operators"))
+            .build()
+            .unwrap();
+        assert_eq!(problem.check("x_1", "{b}").unwrap(), Player::Eve);
+        assert_eq!(problem.check("x_1", "{c}").unwrap(), Player::Adam);
+
+        // The tables are over the powerset of a single basis
+        let tables = |builder: super::ProblemBuilder| {
+            builder
+                .parse_operators(src("arity"))
+                .parse_equations(src("fix_system"))
+                .parse_operator_tables(src("operators"))
+                .build()
+        };
+        let lattice = tables(Problem::builder().parse_lattice("{a} < {b};"));
+        assert!(matches!(lattice, Err(ProblemError::Validation(_))));
+        let named = tables(
+            Problem::builder()
+                .parse_basis(src("basis"))
+                .parse_named_basis("B", src("basis")),
+        );
+        assert!(matches!(named, Err(ProblemError::Validation(_))));
+    }
+
+    /// Each named lattice is checked only against the moves of the operators
+    /// of its own equations, whose atoms may stand for the elements of
+    /// another lattice, and the moves must still be monotone in its order. An
+    /// atom over the lattice must name one of its elements.
+    #[test]
+    fn problem_named_lattices() {
+        let build_with = |equations: &str, moves: &str| {
+            Problem::builder()
+                .parse_operators("f 1\ng 0")
+                .parse_equations(equations)
+                .parse_lattice("bot < d;")
+                .parse_named_lattice("L", "bot < a < c;")
+                .parse_named_lattice("M", "mbot < p < mtop; mbot < q < mtop;")
+                .parse_moves(moves)
+                .build()
+        };
+        let build = |moves: &str| build_with("x : L =max f(y);\ny : M =max g();", moves);
+        let problem = build(
+            "phi(a)(f) = [p, 1] or [q, 1];
+            phi(c)(f) = [q, 1];
+            phi(p)(g) = true;
+            phi(q)(g) = true;",
+        )
+        .unwrap();
+        assert_eq!(problem.check("x", "c").unwrap(), Player::Eve);
+
+        let problem = build(
+            "phi(a)(f) = [p, 1];
+            phi(c)(f) = [q, 1];
+            phi(p)(g) = true;
+            phi(q)(g) = true;",
+        );
+        assert!(matches!(problem, Err(ProblemError::Lattice(_))));
+
+        let problem = build_with(
+            "x : L =max f(x);\ny : M =max g();",
+            "phi(a)(f) = [p, 1]; phi(c)(f) = [p, 1];",
+        );
+        assert!(matches!(problem, Err(ProblemError::Lattice(_))));
+    }
+}
@@ -0,0 +1,304 @@
+//! Semantic checks of the inputs, which are well formed but possibly
+//! inconsistent with each other. Errors make the inputs unusable, e.g. a
+//! variable which is not defined, while warnings point out likely mistakes,
+//! e.g. a symbolic exists-move which is not given and defaults to `false`.
+
+use std::fmt;
+
+use rustc_hash::FxHashMap as HashMap;
+use rustc_hash::FxHashSet as HashSet;
+
+use crate::ast::fixpoint_system::{ExpFixEq, FixEq};
+use crate::ast::symbolic_moves::LogicFormula;
+use crate::normalizer::normalize_system;
+
+/// The problems found in the inputs.
+#[derive(Debug, Default, Clone)]
+pub struct Validation {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidationError {
+    details: String,
+}
+
+impl ValidationError {
+    fn new(details: String) -> ValidationError {
+        ValidationError { details }
+    }
+}
+
+impl std::error::Error for ValidationError {
+    fn description(&self) -> &str {
+        &self.details
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid input:\n- {}", self.details)
+    }
+}
+
+impl Validation {
+    pub fn extend(&mut self, other: Validation) {
+        self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Fails if there are errors, or warnings in strict mode, otherwise
+    /// returns the warnings.
+    pub fn into_result(self, strict: bool) -> Result<Vec<String>, ValidationError> {
+        let mut errors = self.errors;
+        if strict {
+            errors.extend(self.warnings.iter().cloned());
+        }
+        if errors.is_empty() {
+            Ok(self.warnings)
+        } else {
+            Err(ValidationError::new(errors.join("\n- ")))
+        }
+    }
+}
+
+/// Checks that the operators are declared once, and that the system of
+/// fixpoint equations defines each variable once, only uses variables it
+/// defines and declared operators, each with as many arguments as its arity.
+pub fn validate_fix_system(arity: &[(String, usize)], fix_system: &[FixEq]) -> Validation {
+    let mut validation = Validation::default();
+
+    let mut arities = HashMap::default();
+    for (op, n) in arity {
+        if arities.insert(op.as_str(), *n).is_some() {
+            validation
+                .errors
+                .push(format!("the operator {} is declared more than once", op));
+        }
+    }
+
+    let mut defined = HashSet::default();
+    for eq in fix_system {
+        if !defined.insert(eq.var.as_str()) {
+            validation
+                .errors
+                .push(format!("the variable {} is defined more than once", eq.var));
+        }
+    }
+
+    for eq in fix_system {
+        check_exp(&eq.var, &eq.exp, &arities, &defined, &mut validation);
+    }
+    validation
+}
+
+fn check_exp(
+    var: &str,
+    exp: &ExpFixEq,
+    arities: &HashMap<&str, usize>,
+    defined: &HashSet<&str>,
+    validation: &mut Validation,
+) {
+    match exp {
+        ExpFixEq::Id(x) => {
+            if !defined.contains(x.as_str()) {
+                validation.errors.push(format!(
+                    "the variable {} is used in the equation of {}, but it is not defined",
+                    x, var
+                ));
+            }
+        }
+        ExpFixEq::And(l, r) | ExpFixEq::Or(l, r) => {
+            check_exp(var, l, arities, defined, validation);
+            check_exp(var, r, arities, defined, validation);
+        }
+        ExpFixEq::Operator(op, args) => {
+            match arities.get(op.as_str()) {
+                None => validation.errors.push(format!(
+                    "the operator {} is used in the equation of {}, but it is not declared",
+                    op, var
+                )),
+                Some(n) if *n != args.len() => validation.errors.push(format!(
+                    "the operator {} has arity {}, but it is applied to {} arguments in the \
+                     equation of {}",
+                    op,
+                    n,
+                    args.len(),
+                    var
+                )),
+                Some(_) => {}
+            }
+            args.iter()
+                .for_each(|arg| check_exp(var, arg, arities, defined, validation));
+        }
+    }
+}
+
+/// Returns the pairs of a basis element and an operator whose move is needed
+/// to solve the system, where `bases[i]` is the basis of the `i`-th equation:
+/// the operators applied in an equation, with each element of its basis. An
+/// operator nested in the argument of another one is applied to the basis of
+/// that argument, as the normalization assigns it.
+pub fn required_moves(fix_system: &[FixEq], bases: &[Vec<String>]) -> Vec<(String, String)> {
+    let named = fix_system
+        .iter()
+        .zip(bases)
+        .map(|(eq, basis)| (eq.basis.clone(), basis))
+        .collect::<HashMap<_, _>>();
+    let (normalized, _) = normalize_system(fix_system.to_vec());
+    let mut required = vec![];
+    for eq in &normalized {
+        let basis = match named.get(&eq.basis) {
+            Some(basis) => basis,
+            None => continue,
+        };
+        for op in eq.exp.operators() {
+            for b in basis.iter() {
+                let pair = (b.to_owned(), op.to_owned());
+                if !required.contains(&pair) {
+                    required.push(pair);
+                }
+            }
+        }
+    }
+    required
+}
+
+/// Checks the symbolic exists-moves listed as triples `(b, f, formula)`:
+/// `b` must be in the basis and `f` declared, each atom `[b', n]` must refer
+/// to an element of the basis and to an argument of `f`. A move given more
+/// than once, and a pair in `required` without a move, which then defaults to
+/// `false`, are warnings.
+pub fn validate_moves(
+    arity: &[(String, usize)],
+    basis: &[String],
+    required: &[(String, String)],
+    moves: &[(String, String, LogicFormula)],
+) -> Validation {
+    let mut validation = Validation::default();
+    let arities = arity
+        .iter()
+        .map(|(op, n)| (op.as_str(), *n))
+        .collect::<HashMap<_, _>>();
+    let in_basis = basis.iter().map(String::as_str).collect::<HashSet<_>>();
+
+    let mut given = HashSet::default();
+    for (b, op, formula) in moves {
+        if !in_basis.contains(b.as_str()) {
+            validation
+                .errors
+                .push(format!("phi({})({}): {} is not in the basis", b, op, b));
+        }
+        let n = match arities.get(op.as_str()) {
+            Some(n) => *n,
+            None => {
+                validation.errors.push(format!(
+                    "phi({})({}): the operator {} is not declared",
+                    b, op, op
+                ));
+                continue;
+            }
+        };
+        if !given.insert((b.as_str(), op.as_str())) {
+            validation.warnings.push(format!(
+                "phi({})({}) is given more than once, only the last one is used",
+                b, op
+            ));
+        }
+        check_formula(b, op, n, formula, &in_basis, &mut validation);
+    }
+
+    for (b, op) in required {
+        if !given.contains(&(b.as_str(), op.as_str())) {
+            validation.warnings.push(format!(
+                "phi({})({}) is not given, it defaults to false",
+                b, op
+            ));
+        }
+    }
+    validation
+}
+
+fn check_formula(
+    b: &str,
+    op: &str,
+    n: usize,
+    formula: &LogicFormula,
+    in_basis: &HashSet<&str>,
+    validation: &mut Validation,
+) {
+    match formula {
+        LogicFormula::BasisElem(x, i) => {
+            if !in_basis.contains(x.as_str()) {
+                validation
+                    .errors
+                    .push(format!("phi({})({}): {} is not in the basis", b, op, x));
+            }
+            if *i >= n {
+                validation.errors.push(format!(
+                    "phi({})({}): the atom [{}, {}] refers to argument {}, but {} has arity {}",
+                    b,
+                    op,
+                    x,
+                    i + 1,
+                    i + 1,
+                    op,
+                    n
+                ));
+            }
+        }
+        LogicFormula::True | LogicFormula::False => {}
+        LogicFormula::Conj(xs) | LogicFormula::Disj(xs) => xs
+            .iter()
+            .for_each(|x| check_formula(b, op, n, x, in_basis, validation)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{required_moves, validate_fix_system, validate_moves};
+    use crate::fixtures::{self, Example};
+    use crate::parse;
+
+    #[test]
+    fn validate_example_01() {
+        let Example {
+            arity,
+            fix_system,
+            basis,
+            ..
+        } = Example::load("example_01");
+        let moves =
+            parse::parse_symbolic_list(&arity, &basis, fixtures::src("example_01", "symbolic"))
+                .unwrap();
+        let required = required_moves(&fix_system, &vec![basis.clone(); fix_system.len()]);
+        assert_eq!(required.len(), basis.len() * arity.len());
+        assert!(validate_fix_system(&arity, &fix_system)
+            .into_result(true)
+            .is_ok());
+        assert!(validate_moves(&arity, &basis, &required, &moves)
+            .into_result(true)
+            .is_ok());
+
+        let fix_system =
+            parse::parse_fixpoint_system(&arity, "x =max p() and y; x =min box(x);".to_string())
+                .unwrap();
+        assert_eq!(validate_fix_system(&arity, &fix_system).errors.len(), 2);
+
+        // A missing move is only an error in strict mode
+        let moves = &moves[1..];
+        assert!(validate_moves(&arity, &basis, &required, moves)
+            .into_result(false)
+            .is_ok());
+        assert!(validate_moves(&arity, &basis, &required, moves)
+            .into_result(true)
+            .is_err());
+        let moves =
+            parse::parse_symbolic_list(&arity, &basis, "phi({a})(p) = [{a}, 1];".to_string())
+                .unwrap();
+        assert!(!validate_moves(&arity, &basis, &required, &moves)
+            .errors
+            .is_empty());
+    }
+}
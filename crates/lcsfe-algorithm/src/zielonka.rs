@@ -0,0 +1,151 @@
+use crate::powerset_game::player::Player;
+
+/// A parity game where nodes are identified by their index. The winner of a
+/// play is the existential player whenever the greatest priority which occurs
+/// infinitely often is even. Every node must have at least one successor.
+pub struct ParityArena {
+    owner: Vec<Player>,
+    priority: Vec<usize>,
+    succ: Vec<Vec<usize>>,
+    pred: Vec<Vec<usize>>,
+}
+
+/// The winning regions of a `ParityArena`, along with a positional winning
+/// strategy: `strategy[v]` is the successor chosen in `v`, whenever `v` is
+/// controlled by the player who wins from it.
+pub struct ParitySolution {
+    pub winner: Vec<Player>,
+    pub strategy: Vec<Option<usize>>,
+}
+
+impl ParityArena {
+    pub fn new(owner: Vec<Player>, priority: Vec<usize>, succ: Vec<Vec<usize>>) -> ParityArena {
+        let mut pred = vec![vec![]; succ.len()];
+        succ.iter()
+            .enumerate()
+            .for_each(|(v, adj)| adj.iter().for_each(|w| pred[*w].push(v)));
+
+        ParityArena {
+            owner,
+            priority,
+            succ,
+            pred,
+        }
+    }
+
+    /// Solves the game with Zielonka's recursive algorithm.
+    pub fn zielonka(&self) -> ParitySolution {
+        let mut strategy = vec![None; self.succ.len()];
+        let (w_eve, _) = self.solve(&vec![true; self.succ.len()], &mut strategy);
+        ParitySolution {
+            winner: w_eve
+                .into_iter()
+                .map(|x| if x { Player::Eve } else { Player::Adam })
+                .collect(),
+            strategy,
+        }
+    }
+
+    /// Computes the attractor of `target` for player `p` in the subgame
+    /// induced by `game`, that is the set of nodes from which `p` can force
+    /// the play into `target`. The nodes of `p` which are attracted are
+    /// assigned a successor in `strategy`.
+    fn attractor(
+        &self,
+        game: &[bool],
+        target: &[usize],
+        p: &Player,
+        strategy: &mut [Option<usize>],
+    ) -> Vec<bool> {
+        let mut attr = vec![false; game.len()];
+        let mut escapes = vec![0; game.len()];
+        let mut queue = target.to_vec();
+        target.iter().for_each(|v| attr[*v] = true);
+
+        while let Some(v) = queue.pop() {
+            for &u in &self.pred[v] {
+                if !game[u] || attr[u] {
+                    continue;
+                }
+                if &self.owner[u] == p {
+                    attr[u] = true;
+                    strategy[u] = Some(v);
+                    queue.push(u);
+                } else {
+                    if escapes[u] == 0 {
+                        escapes[u] = self.succ[u].iter().filter(|w| game[**w]).count();
+                    }
+                    escapes[u] -= 1;
+                    if escapes[u] == 0 {
+                        attr[u] = true;
+                        queue.push(u);
+                    }
+                }
+            }
+        }
+        attr
+    }
+
+    /// Recursive Zielonka algorithm over the subgame induced by `game`.
+    /// Returns the winning regions of the existential and the universal
+    /// player, respectively.
+    fn solve(&self, game: &[bool], strategy: &mut [Option<usize>]) -> (Vec<bool>, Vec<bool>) {
+        let d = match game
+            .iter()
+            .enumerate()
+            .filter(|(_, in_game)| **in_game)
+            .map(|(v, _)| self.priority[v])
+            .max()
+        {
+            Some(d) => d,
+            None => return (vec![false; game.len()], vec![false; game.len()]),
+        };
+
+        let (p, opponent) = if d % 2 == 0 {
+            (Player::Eve, Player::Adam)
+        } else {
+            (Player::Adam, Player::Eve)
+        };
+
+        let top = (0..game.len())
+            .filter(|v| game[*v] && self.priority[*v] == d)
+            .collect::<Vec<_>>();
+        let attr = self.attractor(game, &top, &p, strategy);
+        let (w_eve, w_adam) = self.solve(&Self::minus(game, &attr), strategy);
+        let w_opponent = if p == Player::Eve { w_adam } else { w_eve };
+
+        if w_opponent.iter().all(|x| !x) {
+            // The nodes with the greatest priority may move anywhere in the
+            // subgame, since every play is won by `p`.
+            top.iter()
+                .filter(|v| self.owner[**v] == p)
+                .for_each(|v| strategy[*v] = self.succ[*v].iter().copied().find(|w| game[*w]));
+            let all = game.to_vec();
+            let none = vec![false; game.len()];
+            return if p == Player::Eve {
+                (all, none)
+            } else {
+                (none, all)
+            };
+        }
+
+        let target = (0..game.len())
+            .filter(|v| w_opponent[*v])
+            .collect::<Vec<_>>();
+        let attr = self.attractor(game, &target, &opponent, strategy);
+        let (mut w_eve, mut w_adam) = self.solve(&Self::minus(game, &attr), strategy);
+        let w_opponent = if p == Player::Eve {
+            &mut w_adam
+        } else {
+            &mut w_eve
+        };
+        (0..game.len())
+            .filter(|v| attr[*v])
+            .for_each(|v| w_opponent[v] = true);
+        (w_eve, w_adam)
+    }
+
+    fn minus(game: &[bool], other: &[bool]) -> Vec<bool> {
+        game.iter().zip(other).map(|(x, y)| *x && !*y).collect()
+    }
+}
@@ -0,0 +1,327 @@
+//! Repeated runs of the verification task of a frontend, on its inputs or on
+//! each file of a directory of inputs, timing the preprocessing and the
+//! solving separately, e.g. to spot regressions between versions.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use lcsfe_common::{Frontend, InputFlags, LcsfeError};
+use serde_json::{json, Value};
+
+/// The times, in seconds, and the peak memory, in KiB, of a phase over the
+/// runs of a task. The memory is missing where it cannot be measured.
+pub struct Phase {
+    times: Vec<f64>,
+    memory: Vec<u64>,
+}
+
+/// The runs of a task, named after its inputs.
+pub struct Measurement {
+    pub task: String,
+    /// Whether the property holds, which is the same in every run
+    pub holds: bool,
+    pub preproc: Phase,
+    pub solve: Phase,
+}
+
+/// The minimum, the median and the maximum of some values.
+struct Stats {
+    min: f64,
+    median: f64,
+    max: f64,
+}
+
+impl Stats {
+    fn of(values: impl IntoIterator<Item = f64>) -> Option<Stats> {
+        let mut values = values.into_iter().collect::<Vec<_>>();
+        values.sort_by(f64::total_cmp);
+        let n = values.len();
+        let median = match n {
+            0 => return None,
+            n if n % 2 == 0 => (values[n / 2 - 1] + values[n / 2]) / 2.0,
+            n => values[n / 2],
+        };
+        Some(Stats {
+            min: values[0],
+            median,
+            max: values[n - 1],
+        })
+    }
+}
+
+impl Phase {
+    fn new() -> Phase {
+        Phase {
+            times: Vec::new(),
+            memory: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, time: f64, memory: Option<u64>) {
+        self.times.push(time);
+        self.memory.extend(memory);
+    }
+
+    fn time(&self) -> Option<Stats> {
+        Stats::of(self.times.iter().copied())
+    }
+
+    fn memory(&self) -> Option<Stats> {
+        // A partial measure would be misleading
+        if self.memory.len() < self.times.len() {
+            return None;
+        }
+        Stats::of(self.memory.iter().map(|x| *x as f64))
+    }
+}
+
+/// The inputs of each task: `inputs` itself, or if one of them is a
+/// directory, the inputs with each file in it in its place, in the order of
+/// the names.
+pub fn tasks(inputs: &[&Path]) -> Result<Vec<Vec<PathBuf>>, LcsfeError> {
+    if inputs.iter().any(|path| lcsfe_common::is_stdin(path)) {
+        return Err(LcsfeError::Query(
+            "The bench command reads the inputs many times, so it cannot read them from stdin"
+                .to_string(),
+        ));
+    }
+    let task = |file: Option<(usize, &Path)>| {
+        inputs
+            .iter()
+            .enumerate()
+            .map(|(i, path)| match file {
+                Some((j, file)) if i == j => file.to_path_buf(),
+                _ => path.to_path_buf(),
+            })
+            .collect::<Vec<_>>()
+    };
+    let dirs = inputs
+        .iter()
+        .enumerate()
+        .filter(|(_, path)| path.is_dir())
+        .map(|(i, _)| i)
+        .collect::<Vec<_>>();
+    match dirs[..] {
+        [] => Ok(vec![task(None)]),
+        [i] => Ok(suite(inputs[i])?
+            .iter()
+            .map(|file| task(Some((i, file))))
+            .collect()),
+        _ => Err(LcsfeError::Query(
+            "Only one of the inputs can be a directory".to_string(),
+        )),
+    }
+}
+
+/// The files in `dir`, sorted, skipping the hidden ones.
+fn suite(dir: &Path) -> Result<Vec<PathBuf>, LcsfeError> {
+    let mut files = std::fs::read_dir(dir)
+        .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| LcsfeError::io(dir, e))?
+        .into_iter()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    if files.is_empty() {
+        return Err(LcsfeError::Query(format!(
+            "{}: the directory has no inputs",
+            dir.display()
+        )));
+    }
+    Ok(files)
+}
+
+/// Runs the task of `frontend` on `inputs` `warmup` times, whose results are
+/// discarded, and then `runs` times.
+pub fn bench(
+    frontend: &dyn Frontend,
+    inputs: &[&Path],
+    flags: &InputFlags,
+    warmup: usize,
+    runs: usize,
+) -> Result<Measurement, LcsfeError> {
+    let mut measurement = Measurement {
+        task: inputs
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" "),
+        holds: false,
+        preproc: Phase::new(),
+        solve: Phase::new(),
+    };
+    for run in 0..warmup + runs {
+        // The parsing is part of the preprocessing, as in a run of the task
+        let memory = reset_peak_memory();
+        let start = Instant::now();
+        let spec = frontend.spec(inputs, None)?;
+        let pre_proc = spec.pre_proc(flags)?;
+        let preproc_time = start.elapsed().as_secs_f64();
+        let preproc_memory = memory.then(peak_memory).flatten();
+
+        let memory = reset_peak_memory();
+        let start = Instant::now();
+        let result = spec.verify(flags, &pre_proc)?;
+        let solve_time = start.elapsed().as_secs_f64();
+        let solve_memory = memory.then(peak_memory).flatten();
+
+        let holds = result.verdict.holds();
+        if run > 0 && holds != measurement.holds {
+            return Err(LcsfeError::Validation(format!(
+                "{}: the verdict changed between runs",
+                measurement.task
+            )));
+        }
+        measurement.holds = holds;
+        if run >= warmup {
+            measurement.preproc.push(preproc_time, preproc_memory);
+            measurement.solve.push(solve_time, solve_memory);
+        }
+    }
+    Ok(measurement)
+}
+
+/// Resets the peak resident set size of the process to the current one, so
+/// that `peak_memory` measures the phase which follows. It is supported on
+/// Linux only, and it returns whether it succeeded.
+fn reset_peak_memory() -> bool {
+    std::fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+/// The peak resident set size of the process, in KiB.
+fn peak_memory() -> Option<u64> {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+impl fmt::Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: the property {}, {} runs",
+            self.task,
+            if self.holds { "holds" } else { "does not hold" },
+            self.preproc.times.len()
+        )?;
+        for (name, phase) in [("Preprocessing", &self.preproc), ("Solving", &self.solve)] {
+            if let Some(time) = phase.time() {
+                write!(
+                    f,
+                    "\n  {} time: min {:.6} sec., median {:.6} sec., max {:.6} sec.",
+                    name, time.min, time.median, time.max
+                )?;
+            }
+            if let Some(memory) = phase.memory() {
+                write!(
+                    f,
+                    "\n  {} peak memory: min {:.0} KiB, median {:.0} KiB, max {:.0} KiB",
+                    name, memory.min, memory.median, memory.max
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub const CSV_HEADER: &str = "task,holds,runs,\
+preproc_min_sec,preproc_median_sec,preproc_max_sec,\
+preproc_min_kib,preproc_median_kib,preproc_max_kib,\
+solve_min_sec,solve_median_sec,solve_max_sec,\
+solve_min_kib,solve_median_kib,solve_max_kib";
+
+impl Measurement {
+    /// A row under `CSV_HEADER`, where the memory is empty if it cannot be
+    /// measured.
+    pub fn csv(&self) -> String {
+        let mut row = vec![
+            csv_field(&self.task),
+            self.holds.to_string(),
+            self.preproc.times.len().to_string(),
+        ];
+        for phase in [&self.preproc, &self.solve] {
+            for (stats, precision) in [(phase.time(), 6), (phase.memory(), 0)] {
+                match stats {
+                    Some(s) => {
+                        row.extend([s.min, s.median, s.max].map(|x| format!("{:.*}", precision, x)))
+                    }
+                    None => row.extend([String::new(), String::new(), String::new()]),
+                }
+            }
+        }
+        row.join(",")
+    }
+
+    pub fn json(&self) -> Value {
+        let phase = |phase: &Phase| {
+            let stats = |stats: Option<Stats>| {
+                stats.map(|s| json!({ "min": s.min, "median": s.median, "max": s.max }))
+            };
+            json!({ "time": stats(phase.time()), "memory": stats(phase.memory()) })
+        };
+        json!({
+            "task": self.task,
+            "holds": self.holds,
+            "runs": self.preproc.times.len(),
+            "preproc": phase(&self.preproc),
+            "solve": phase(&self.solve),
+        })
+    }
+}
+
+/// Quotes a field which contains a separator or a quote.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Measurement, Phase};
+
+    /// The median of an even number of runs is the mean of the two middle
+    /// ones, the memory is left empty if some run did not measure it, and the
+    /// task is quoted if it contains a separator.
+    #[test]
+    fn csv_row() {
+        let measurement = Measurement {
+            task: "a,b".to_string(),
+            holds: true,
+            preproc: Phase {
+                times: vec![0.3, 0.1, 0.4, 0.2],
+                memory: vec![40, 10, 30, 20],
+            },
+            solve: Phase {
+                times: vec![0.5; 4],
+                memory: vec![10],
+            },
+        };
+        assert_eq!(
+            measurement.csv(),
+            "\"a,b\",true,4,0.100000,0.250000,0.400000,10,25,40,0.500000,0.500000,0.500000,,,"
+        );
+        assert_eq!(
+            measurement.to_string(),
+            "a,b: the property holds, 4 runs\n  \
+             Preprocessing time: min 0.100000 sec., median 0.250000 sec., max 0.400000 sec.\n  \
+             Preprocessing peak memory: min 10 KiB, median 25 KiB, max 40 KiB\n  \
+             Solving time: min 0.500000 sec., median 0.500000 sec., max 0.500000 sec."
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use lcsfe_algorithm::problem::{Problem, ProblemBuilder};
+use lcsfe_common::{LcsfeError, PreProcOutput};
+
+/// The bases of the `debug` command: the default one, and the ones of the
+/// equations which declare a basis by name. If `lattice` is enabled, they are
+/// lattices, whose join-irreducible elements are the basis.
+pub struct Bases {
+    pub default: std::path::PathBuf,
+    pub named: Vec<(String, std::path::PathBuf)>,
+    pub lattice: bool,
+}
+
+/// The inputs of the `debug` command, read into a `Problem`, whose equations
+/// are referred to by their position, starting from 1.
+pub struct DebugInputs {
+    pub problem: Problem,
+}
+
+impl DebugInputs {
+    /// Reads the inputs of the `debug` command, where the symbolic
+    /// exists-moves are derived from the operator tables if `operators` is
+    /// enabled, and builds the problem, whose warnings are printed.
+    pub fn load(
+        arity: &std::path::Path,
+        fix_system: &std::path::Path,
+        bases: &Bases,
+        moves_system: &std::path::Path,
+        operators: bool,
+        strict: bool,
+        normalize: bool,
+    ) -> Result<DebugInputs, LcsfeError> {
+        let read = lcsfe_common::read_input;
+        let mut builder = Problem::builder()
+            .parse_operators(read(arity)?)
+            .parse_equations(read(fix_system)?)
+            .normalize(normalize)
+            .strict(strict);
+        builder = basis(builder, None, read(&bases.default)?, bases.lattice);
+        for (name, path) in &bases.named {
+            builder = basis(builder, Some(name), read(path)?, bases.lattice);
+        }
+        builder = if operators {
+            builder.parse_operator_tables(read(moves_system)?)
+        } else {
+            builder.parse_moves(read(moves_system)?)
+        };
+
+        let problem = builder.build()?;
+        for warning in problem.warnings() {
+            eprintln!("Warning: {}", warning);
+        }
+        Ok(DebugInputs { problem })
+    }
+
+    /// The preprocessing of the problem, normalized if requested. Its
+    /// variable is the one of the first equation.
+    pub fn pre_proc(&mut self, normalize: bool) -> Result<PreProcOutput, LcsfeError> {
+        self.problem.set_normalize(normalize)?;
+        Ok(PreProcOutput {
+            moves: self.problem.moves().clone(),
+            fix_system: self.problem.fix_system().to_vec(),
+            var_map: self.problem.var_map().clone(),
+            var: self
+                .problem
+                .original()
+                .first()
+                .map(|eq| eq.var.to_owned())
+                .unwrap_or_default(),
+            preproc_time: self.problem.compose_time(),
+            cached: false,
+        })
+    }
+
+    /// The variable of the `position`-th equation, and its index in the
+    /// system which is solved.
+    pub fn index(&self, position: usize) -> Result<(String, usize), LcsfeError> {
+        let var_name = position
+            .checked_sub(1)
+            .and_then(|i| self.problem.original().get(i))
+            .map(|x| x.var.to_owned())
+            .ok_or_else(|| {
+                LcsfeError::Query(format!("Cannot find variable with index {}", position))
+            })?;
+        let index = self.problem.index(&var_name)?;
+        Ok((var_name, index))
+    }
+
+    /// The basis elements which the join of `xs` stands for in the basis of
+    /// the `position`-th equation, which must belong to it, see
+    /// `Problem::elements`.
+    pub fn elements(&self, position: usize, xs: &[String]) -> Result<Vec<String>, LcsfeError> {
+        let (var_name, index) = self.index(position)?;
+        let elements = self.problem.elements(&var_name, xs)?;
+        self.problem.check_basis(&var_name, index, &elements)?;
+        Ok(elements)
+    }
+}
+
+/// Adds the basis named `name`, or the default one, as a lattice if
+/// `lattice` is enabled.
+fn basis(
+    builder: ProblemBuilder,
+    name: Option<&String>,
+    src: String,
+    lattice: bool,
+) -> ProblemBuilder {
+    match (name, lattice) {
+        (None, false) => builder.parse_basis(src),
+        (None, true) => builder.parse_lattice(src),
+        (Some(name), false) => builder.parse_named_basis(name, src),
+        (Some(name), true) => builder.parse_named_lattice(name, src),
+    }
+}
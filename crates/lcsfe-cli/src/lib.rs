@@ -0,0 +1,944 @@
+//! The command line interface. It builds a command for each frontend in its
+//! registry, so that a crate which implements a new frontend can register it
+//! and call [`main_with`], in place of forking the interface.
+
+mod bench;
+mod debug;
+mod repl;
+
+use std::time::{Duration, Instant};
+
+use clap::{
+    builder::{PossibleValue, PossibleValuesParser},
+    Arg, ArgAction, ArgMatches, CommandFactory, FromArgMatches, Subcommand, ValueEnum,
+};
+use lcsfe_algorithm::{
+    algorithm::{Player, Query},
+    ast::{
+        fixpoint_system::alternation_depth, operator_table::validate_symbolic_moves,
+        symbolic_moves::SymbolicExistsMoves as UncomposedMoves,
+    },
+    validation::{validate_moves, Validation},
+};
+use lcsfe_common::{
+    json_batch_output, json_output, BatchOutput, CheckpointFlags, Frontend, InputFlags, LcsfeError,
+    LintOutput, PreProcOutput, Registry, SpecOutput, Verdict, VerdictQuery, VerificationOutput,
+};
+use lcsfe_mu_ald::MuAldFrontend;
+use lcsfe_pg::ParityGameFrontend;
+use serde_json::json;
+
+use debug::{Bases, DebugInputs};
+use repl::{Model, Repl};
+
+#[derive(Debug, clap::Parser)]
+#[command(about = "A local model checker which leverages parity games and symbolic exists-moves", long_about = None)]
+struct Cli {
+    /// If enabled, the underlying system of fixpoint equations is normalized
+    /// during the preprocessing phase
+    #[arg(short, long)]
+    normalize: bool,
+    /// The format of the output, either prose or a JSON document for scripts
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// If enabled, the warnings about the inputs, e.g. a symbolic exists-move
+    /// which is not given and defaults to false, are errors
+    #[arg(long)]
+    strict: bool,
+    #[arg(short, long)]
+    /// If enabled, prints to stdout the underlying system of fixpoint equations
+    /// before and after normalization, and the symbolic exists-moves, before
+    /// and after composition. It does so only after the computation
+    explain: bool,
+    /// A path to a file where the state of the local algorithm is
+    /// periodically saved, so that the run can be resumed with `--resume`
+    #[arg(long)]
+    checkpoint: Option<std::path::PathBuf>,
+    /// How often, in seconds, the state of the local algorithm is saved
+    #[arg(long, default_value_t = 300)]
+    checkpoint_every: u64,
+    /// A path to a file written with `--checkpoint`, from which the local
+    /// algorithm resumes. The inputs and the query must be the same of the
+    /// run which wrote it
+    #[arg(long)]
+    resume: Option<std::path::PathBuf>,
+    /// A directory where the preprocessing of the commands of the frontends
+    /// is stored, keyed by the content of the input files, and read back by
+    /// later runs on the same inputs, e.g. for another query
+    #[arg(long)]
+    cache: Option<std::path::PathBuf>,
+    /// Missing for the commands of the frontends, which are not known until
+    /// the registry is
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    #[command(arg_required_else_help = true)]
+    /// A user should choose this command only if willing to deal with the
+    /// underlying engine.
+    Debug {
+        /// A path to a file containing the operators and arity
+        arity: std::path::PathBuf,
+        /// A path to a file containing a system of fixpoint equations
+        fix_system: std::path::PathBuf,
+        /// A path to a file containing the basis
+        basis: std::path::PathBuf,
+        /// A path to a file containing the symbolic exists-moves, for each
+        /// basis element and function, to be composed
+        moves_system: std::path::PathBuf,
+        /// If enabled, the file `moves_system` contains a table for each
+        /// operator, from which the symbolic exists-moves are derived
+        #[arg(short, long)]
+        operators: bool,
+        /// If enabled, the file `basis` contains the order relation of a
+        /// finite lattice, whose join-irreducible elements are the basis
+        #[arg(short, long, conflicts_with = "operators")]
+        lattice: bool,
+        /// A basis for the equations which declare it by name, as in
+        /// `x : NAME =max ...`, while the others range over `basis`. It is a
+        /// lattice as well if `--lattice` is enabled
+        #[arg(short = 'B', long = "named-basis", value_name = "NAME=PATH", value_parser = named_path)]
+        named_bases: Vec<(String, std::path::PathBuf)>,
+        /// A string representing the element of the basis whose membership in
+        /// the solution you want to verify
+        element_of_basis: String,
+        /// The index of the fixpoint equation from which you want to start the
+        /// analysis, it starts from 1
+        index: usize,
+        /// A path to a file where a certificate for the result is written,
+        /// which can be validated with the `check-cert` command
+        #[arg(short, long)]
+        certificate: Option<std::path::PathBuf>,
+        /// Further elements, the query is about the join of all the elements
+        #[arg(short, long = "join", value_name = "ELEMENT")]
+        join: Vec<String>,
+        /// Whether the join of the elements is below the solution, above it,
+        /// or exactly the solution
+        #[arg(short, long, value_enum, default_value_t = QueryKind::Below)]
+        query: QueryKind,
+    },
+
+    #[command(arg_required_else_help = true)]
+    /// Validates a certificate produced by the `debug` command, without
+    /// running the local algorithm. The normalization flag must be the same
+    /// used to produce the certificate.
+    CheckCert {
+        /// A path to a file containing the operators and arity
+        arity: std::path::PathBuf,
+        /// A path to a file containing a system of fixpoint equations
+        fix_system: std::path::PathBuf,
+        /// A path to a file containing the basis
+        basis: std::path::PathBuf,
+        /// A path to a file containing the symbolic exists-moves, for each
+        /// basis element and function, to be composed
+        moves_system: std::path::PathBuf,
+        /// If enabled, the file `moves_system` contains a table for each
+        /// operator, from which the symbolic exists-moves are derived
+        #[arg(short, long)]
+        operators: bool,
+        /// If enabled, the file `basis` contains the order relation of a
+        /// finite lattice, whose join-irreducible elements are the basis
+        #[arg(short, long, conflicts_with = "operators")]
+        lattice: bool,
+        /// A basis for the equations which declare it by name, as in
+        /// `x : NAME =max ...`, while the others range over `basis`. It is a
+        /// lattice as well if `--lattice` is enabled
+        #[arg(short = 'B', long = "named-basis", value_name = "NAME=PATH", value_parser = named_path)]
+        named_bases: Vec<(String, std::path::PathBuf)>,
+        /// A path to a file containing the certificate
+        certificate: std::path::PathBuf,
+    },
+
+    #[command(arg_required_else_help = true)]
+    /// Checks that the symbolic exists-moves are correct with respect to the
+    /// tables of the operators, i.e. that each move is sound and that the
+    /// minimal moves are allowed.
+    ValidateMoves {
+        /// A path to a file containing the operators and arity
+        arity: std::path::PathBuf,
+        /// A path to a file containing the basis
+        basis: std::path::PathBuf,
+        /// A path to a file containing the symbolic exists-moves, for each
+        /// basis element and function
+        moves_system: std::path::PathBuf,
+        /// A path to a file containing a table for each operator, as read by
+        /// the `--operators` flag of the `debug` command
+        operators: std::path::PathBuf,
+    },
+
+    /// Parses and validates the inputs of a command, and summarises them
+    /// without solving. It exits with a non-zero code if the inputs have
+    /// errors, or warnings when `--strict` is enabled.
+    Lint {
+        /// Missing for the commands of the frontends
+        #[command(subcommand)]
+        command: Option<InputCommands>,
+    },
+
+    /// Loads the inputs of a command once, and then reads commands from
+    /// stdin, e.g. to verify a property from many states, or to replace the
+    /// formula. Type `help` in the session for the list of commands.
+    Repl {
+        /// Missing for the commands of the frontends
+        #[command(subcommand)]
+        command: Option<InputCommands>,
+    },
+
+    /// Runs the verification task of a command many times, from its default
+    /// position, and reports the minimum, the median and the maximum of the
+    /// times and of the peak memory, of the preprocessing and of the solving.
+    /// An input can be a directory, to run the task on each file in it.
+    Bench {
+        /// The number of runs which are measured
+        #[arg(short, long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+        runs: u64,
+        /// The number of runs before those measured, whose results are
+        /// discarded
+        #[arg(short, long, default_value_t = 1)]
+        warmup: u64,
+        /// If enabled, the results are printed as CSV, a row for each task
+        #[arg(long)]
+        csv: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum InputCommands {
+    #[command(arg_required_else_help = true)]
+    /// The inputs of the `debug` command
+    Debug {
+        /// A path to a file containing the operators and arity
+        arity: std::path::PathBuf,
+        /// A path to a file containing a system of fixpoint equations
+        fix_system: std::path::PathBuf,
+        /// A path to a file containing the basis
+        basis: std::path::PathBuf,
+        /// A path to a file containing the symbolic exists-moves, for each
+        /// basis element and function, to be composed
+        moves_system: std::path::PathBuf,
+        /// If enabled, the file `moves_system` contains a table for each
+        /// operator, from which the symbolic exists-moves are derived
+        #[arg(short, long)]
+        operators: bool,
+        /// If enabled, the file `basis` contains the order relation of a
+        /// finite lattice, whose join-irreducible elements are the basis
+        #[arg(short, long, conflicts_with = "operators")]
+        lattice: bool,
+        /// A basis for the equations which declare it by name, as in
+        /// `x : NAME =max ...`, while the others range over `basis`
+        #[arg(short = 'B', long = "named-basis", value_name = "NAME=PATH", value_parser = named_path)]
+        named_bases: Vec<(String, std::path::PathBuf)>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ValueEnum)]
+enum QueryKind {
+    Below,
+    Above,
+    Exact,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// The exit code when the property does not hold, i.e. the universal player
+/// wins. A solving command exits with 0 when the property holds, and the
+/// other commands when they succeed.
+const NOT_SATISFIED: i32 = 1;
+/// The exit code on errors, which is also the one of clap on invalid
+/// arguments.
+const ERROR: i32 = 2;
+
+/// The registry of the frontends of this crate.
+pub fn default_registry() -> Registry {
+    let mut registry = Registry::default();
+    registry.register(ParityGameFrontend);
+    registry.register(MuAldFrontend);
+    registry
+}
+
+/// Parses the arguments of the process and runs the command, with a command
+/// for each frontend in `registry`, then exits.
+pub fn main_with(registry: Registry) {
+    let mut command = Cli::command();
+    for frontend in registry.iter() {
+        command = command.subcommand(frontend_command(frontend));
+    }
+    command = command.subcommand_required(true);
+    for name in ["lint", "repl", "bench"] {
+        command = command.mut_subcommand(name, |inputs| {
+            registry
+                .iter()
+                .fold(inputs, |inputs, frontend| {
+                    inputs.subcommand(frontend_inputs_command(frontend))
+                })
+                .subcommand_required(true)
+        });
+    }
+    let matches = command.get_matches();
+    let args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let format = args.format.clone();
+    match run(args, &matches, &registry) {
+        Ok(Some(Player::Adam)) => std::process::exit(NOT_SATISFIED),
+        Ok(_) => {}
+        Err(e) => {
+            match format {
+                Format::Text => eprintln!("{}", e),
+                Format::Json => println!(
+                    "{}",
+                    json!({ "error": { "kind": e.kind(), "message": e.to_string() } })
+                ),
+            }
+            std::process::exit(ERROR)
+        }
+    }
+}
+
+/// The command which solves the verification tasks of a frontend, whose
+/// arguments are its inputs and its queries, all of them with `--all`.
+fn frontend_command(frontend: &dyn Frontend) -> clap::Command {
+    let query = frontend.query();
+    let command = frontend_inputs_command(frontend)
+        .about(frontend.about())
+        .arg(
+            Arg::new(query.name)
+                .help(query.help)
+                .num_args(1..)
+                .required_unless_present("all"),
+        )
+        .arg(
+            Arg::new("all")
+                .long("all")
+                .help(format!(
+                    "Verifies the property from every {}, sharing the preprocessing",
+                    query.name
+                ))
+                .action(ArgAction::SetTrue)
+                .conflicts_with(query.name),
+        );
+    if frontend.solvers().is_empty() {
+        return command;
+    }
+    let solvers = std::iter::once(
+        PossibleValue::new("local")
+            .help("The local algorithm, on the translated system of fixpoint equations"),
+    )
+    .chain(
+        frontend
+            .solvers()
+            .iter()
+            .map(|solver| PossibleValue::new(solver.name).help(solver.help)),
+    );
+    command.arg(
+        Arg::new("solver")
+            .short('s')
+            .long("solver")
+            .help("The algorithm used to solve the verification task")
+            .default_value("local")
+            .value_parser(PossibleValuesParser::new(solvers)),
+    )
+}
+
+/// The command which loads the inputs of a frontend, to lint them or to
+/// start a session.
+fn frontend_inputs_command(frontend: &dyn Frontend) -> clap::Command {
+    frontend.inputs().iter().fold(
+        clap::Command::new(frontend.name())
+            .about(format!("The inputs of the `{}` command", frontend.name()))
+            .arg_required_else_help(true),
+        |command, input| {
+            command.arg(
+                Arg::new(input.name)
+                    .help(input.help)
+                    .required(true)
+                    .value_parser(clap::value_parser!(std::path::PathBuf)),
+            )
+        },
+    )
+}
+
+/// The frontend of the subcommand in `matches`, along with its arguments.
+fn frontend_matches<'a>(
+    registry: &'a Registry,
+    matches: Option<&'a ArgMatches>,
+) -> Result<(&'a dyn Frontend, &'a ArgMatches), LcsfeError> {
+    matches
+        .and_then(ArgMatches::subcommand)
+        .and_then(|(name, sub)| Some((registry.get(name)?, sub)))
+        .ok_or_else(|| LcsfeError::Query("Unknown command".to_string()))
+}
+
+/// The paths of the inputs of a frontend, in order.
+fn frontend_inputs<'a>(
+    frontend: &dyn Frontend,
+    matches: &'a ArgMatches,
+) -> Vec<&'a std::path::Path> {
+    frontend
+        .inputs()
+        .iter()
+        .filter_map(|input| matches.get_one::<std::path::PathBuf>(input.name))
+        .map(|path| path.as_path())
+        .collect()
+}
+
+/// Runs the command, and returns the winner if the command solves a game.
+fn run(args: Cli, matches: &ArgMatches, registry: &Registry) -> Result<Option<Player>, LcsfeError> {
+    let format = args.format;
+    let normalize = args.normalize;
+    let strict = args.strict;
+    let explain = args.explain;
+    let checkpoint = |inputs: &[&std::path::Path], options: &[String]| {
+        input_flags(
+            normalize,
+            args.checkpoint.clone(),
+            args.resume.clone(),
+            Duration::from_secs(args.checkpoint_every),
+            inputs,
+            options,
+        )
+    };
+
+    let winner = match args.command {
+        Some(Commands::Debug {
+            arity,
+            fix_system,
+            basis,
+            moves_system,
+            operators,
+            lattice,
+            named_bases,
+            element_of_basis: basis_element,
+            index: position,
+            certificate,
+            join,
+            query,
+        }) => {
+            let mut inputs = vec![&arity, &fix_system, &basis, &moves_system];
+            inputs.extend(named_bases.iter().map(|(_, path)| path));
+            // How the inputs are read, besides their content
+            let mut options = vec![
+                format!("operators={}", operators),
+                format!("lattice={}", lattice),
+            ];
+            options.extend(
+                named_bases
+                    .iter()
+                    .map(|(name, _)| format!("basis={}", name)),
+            );
+            let input_flags = checkpoint(
+                &inputs.iter().map(|x| x.as_path()).collect::<Vec<_>>(),
+                &options,
+            )?;
+            let bases = Bases {
+                default: basis,
+                named: named_bases,
+                lattice,
+            };
+            let mut inputs = DebugInputs::load(
+                &arity,
+                &fix_system,
+                &bases,
+                &moves_system,
+                operators,
+                strict,
+                normalize,
+            )?;
+            let (var_name, index) = inputs.index(position)?;
+            let pos = (basis_element, index);
+
+            let mut elements = inputs.elements(
+                position,
+                &std::iter::once(pos.0).chain(join).collect::<Vec<_>>(),
+            )?;
+            let single = elements.len() == 1 && query == QueryKind::Below;
+            if !single && (certificate.is_some() || input_flags.checkpoint.is_some()) {
+                return Err(LcsfeError::Query(
+                    "Certificates and checkpoints are only supported for a single basis element"
+                        .to_string(),
+                ));
+            }
+            if certificate.is_some() && input_flags.checkpoint.is_some() {
+                return Err(LcsfeError::Query(
+                    "Certificates cannot be combined with checkpoints".to_string(),
+                ));
+            }
+
+            let query_json = json!({
+                "elements": elements,
+                "index": position,
+                "variable": var_name,
+                "kind": format!("{:?}", query).to_lowercase(),
+            });
+
+            let preproc = PreProcOutput {
+                var: var_name,
+                ..inputs.pre_proc(normalize)?
+            };
+            print_preproc(&preproc, explain, &format);
+
+            let parity_game = lcsfe_algorithm::algorithm::LocalAlgorithm {
+                symbolic_moves: &preproc.moves,
+                fix_system: &preproc.fix_system,
+                up_to: None,
+            };
+
+            let verdict_query = VerdictQuery {
+                basis_elements: elements.clone(),
+                index: pos.1,
+                variable: preproc.fix_system[pos.1].var.to_owned(),
+                original: preproc.var.to_owned(),
+            };
+            let mut evidence = None;
+
+            let start = Instant::now();
+            let winner = if !single {
+                let query = match query {
+                    QueryKind::Below => Query::Below(elements, pos.1),
+                    QueryKind::Above => Query::Above(elements, pos.1),
+                    QueryKind::Exact => Query::Exact(elements, pos.1),
+                };
+                parity_game.check_query(&query)?
+            } else {
+                let b = elements.remove(0);
+                match certificate {
+                    Some(path) => {
+                        let (winner, certificate) = parity_game.local_check_certified(b, pos.1)?;
+                        std::fs::write(&path, certificate.to_string())
+                            .map_err(|e| LcsfeError::io(&path, e))?;
+                        evidence = Some(certificate);
+                        winner
+                    }
+                    None => lcsfe_common::local_check(&parity_game, b, pos.1, &input_flags)?,
+                }
+            };
+            let algo_time = start.elapsed();
+
+            let verdict = Verdict {
+                winner,
+                query: verdict_query,
+                evidence,
+                statistics: Some(preproc.statistics()),
+            };
+            let result = VerificationOutput {
+                algorithm_time: algo_time,
+                result: verdict.to_string(),
+                verdict,
+            };
+            print_verification(&result, Some(&preproc), query_json, &format);
+            Some(result.verdict.winner)
+        }
+
+        Some(Commands::CheckCert {
+            arity,
+            fix_system,
+            basis,
+            moves_system,
+            operators,
+            lattice,
+            named_bases,
+            certificate,
+        }) => {
+            let bases = Bases {
+                default: basis,
+                named: named_bases,
+                lattice,
+            };
+            let problem = DebugInputs::load(
+                &arity,
+                &fix_system,
+                &bases,
+                &moves_system,
+                operators,
+                strict,
+                normalize,
+            )?
+            .problem;
+            let composed_system = problem.moves();
+
+            let basis = (0..composed_system.basis_len())
+                .map(|b| composed_system.get_basis_elem(b).to_owned())
+                .collect::<Vec<_>>();
+            let certificate = lcsfe_algorithm::parse::parse_certificate(
+                &basis,
+                lcsfe_common::read_input(&certificate)?,
+            )?;
+
+            certificate.check(problem.fix_system(), composed_system)?;
+            print_message(
+                &format!(
+                    "The certificate is valid: the winner from {} is the {}",
+                    certificate.query, certificate.winner
+                ),
+                &format,
+            );
+            None
+        }
+
+        Some(Commands::ValidateMoves {
+            arity,
+            basis,
+            moves_system,
+            operators,
+        }) => {
+            let arity_src = lcsfe_common::read_input(&arity)?;
+            let basis_src = lcsfe_common::read_input(&basis)?;
+            let moves_src = lcsfe_common::read_input(&moves_system)?;
+            let operators_src = lcsfe_common::read_input(&operators)?;
+
+            let arity = lcsfe_algorithm::parse::parse_fun_arity(arity_src)?;
+            let basis = lcsfe_algorithm::parse::parse_basis(basis_src)?;
+            let moves_system =
+                lcsfe_algorithm::parse::parse_symbolic_list(&arity, &basis, moves_src)?;
+            let required = arity
+                .iter()
+                .flat_map(|(op, _)| basis.iter().map(|b| (b.to_owned(), op.to_owned())))
+                .collect::<Vec<_>>();
+            report(
+                validate_moves(&arity, &basis, &required, &moves_system),
+                strict,
+            )?;
+            let moves_system = UncomposedMoves::from_list(&arity, &basis, moves_system);
+            let tables = lcsfe_algorithm::parse::parse_operators(&arity, &basis, operators_src)?;
+
+            validate_symbolic_moves(&arity, &basis, &tables, &moves_system)?;
+            print_message("The symbolic exists-moves are correct", &format);
+            None
+        }
+
+        None => {
+            let (frontend, sub) = frontend_matches(registry, Some(matches))?;
+            let inputs = frontend_inputs(frontend, sub);
+            let input_flags = checkpoint(&inputs, &[])?;
+            // With the cache, the inputs are not parsed if the preprocessing
+            // is found
+            let pre_proc = |query: Option<String>| match &args.cache {
+                Some(dir) => lcsfe_common::cached_spec(frontend, &inputs, query, &input_flags, dir),
+                None => {
+                    let spec = frontend.spec(&inputs, query)?;
+                    let pre_proc = spec.pre_proc(&input_flags)?;
+                    Ok((spec, pre_proc))
+                }
+            };
+            let query = frontend.query();
+            let mut queries = sub
+                .get_many::<String>(query.name)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>();
+            // Only the frontends with other solvers have the argument
+            let solver = sub.try_get_one::<String>("solver").ok().flatten();
+            let solver = solver.map(String::as_str).filter(|x| *x != "local");
+
+            if queries.len() == 1 && !sub.get_flag("all") {
+                let query_value = queries.remove(0);
+                let query_json = json!({ query.name: query_value });
+                match solver {
+                    None => {
+                        let (spec, preproc) = pre_proc(Some(query_value))?;
+                        Some(print_results(
+                            spec.as_ref(),
+                            preproc,
+                            explain,
+                            input_flags,
+                            query_json,
+                            &format,
+                        )?)
+                    }
+                    Some(solver) => {
+                        let spec = frontend.spec(&inputs, Some(query_value.clone()))?;
+                        let result = spec.verify_with(solver, &query_value)?;
+                        print_verification(&result, None, query_json, &format);
+                        Some(result.verdict.winner)
+                    }
+                }
+            } else {
+                if input_flags.checkpoint.is_some() {
+                    return Err(LcsfeError::Query(
+                        "Checkpoints are only supported for a single query".to_string(),
+                    ));
+                }
+                let (preproc, results) = match solver {
+                    None => {
+                        let (spec, preproc) = pre_proc(queries.first().cloned())?;
+                        if queries.is_empty() {
+                            queries = spec.positions();
+                        }
+                        print_preproc(&preproc, explain, &format);
+                        let results = spec.verify_many(&input_flags, &preproc, &queries)?;
+                        (Some(preproc), results)
+                    }
+                    Some(solver) => {
+                        let spec = frontend.spec(&inputs, queries.first().cloned())?;
+                        if queries.is_empty() {
+                            queries = spec.positions();
+                        }
+                        (None, spec.verify_many_with(solver, &queries)?)
+                    }
+                };
+                let batch = BatchOutput {
+                    query: query.name.to_string(),
+                    results,
+                };
+                match format {
+                    Format::Text => println!("{}", batch),
+                    Format::Json => println!("{}", json_batch_output(preproc.as_ref(), &batch)),
+                }
+                // The property holds only if it holds from every position
+                Some(if batch.satisfied() == batch.results.len() {
+                    Player::Eve
+                } else {
+                    Player::Adam
+                })
+            }
+        }
+        Some(Commands::Lint {
+            command:
+                Some(InputCommands::Debug {
+                    arity,
+                    fix_system,
+                    basis,
+                    moves_system,
+                    operators,
+                    lattice,
+                    named_bases,
+                }),
+        }) => {
+            let bases = Bases {
+                default: basis,
+                named: named_bases,
+                lattice,
+            };
+            let problem = DebugInputs::load(
+                &arity,
+                &fix_system,
+                &bases,
+                &moves_system,
+                operators,
+                strict,
+                normalize,
+            )?
+            .problem;
+
+            let mut ops = problem
+                .original()
+                .iter()
+                .flat_map(|eq| eq.exp.operators())
+                .collect::<Vec<_>>();
+            ops.sort();
+            ops.dedup();
+
+            let mut lint = LintOutput::default();
+            lint.push("Equations", problem.fix_system().len());
+            lint.push("Alternation depth", alternation_depth(problem.fix_system()));
+            lint.push("Basis size", problem.moves().basis_len());
+            lint.push(
+                "Operators used",
+                ops.into_iter().cloned().collect::<Vec<_>>(),
+            );
+            print_lint(lint, strict, &format)?;
+            None
+        }
+        Some(Commands::Lint { command: None }) => {
+            let (frontend, sub) = frontend_matches(registry, matches.subcommand_matches("lint"))?;
+            print_lint(
+                frontend.lint(&frontend_inputs(frontend, sub))?,
+                strict,
+                &format,
+            )?;
+            None
+        }
+        Some(Commands::Repl {
+            command:
+                Some(InputCommands::Debug {
+                    arity,
+                    fix_system,
+                    basis,
+                    moves_system,
+                    operators,
+                    lattice,
+                    named_bases,
+                }),
+        }) => {
+            let bases = Bases {
+                default: basis,
+                named: named_bases,
+                lattice,
+            };
+            let inputs = DebugInputs::load(
+                &arity,
+                &fix_system,
+                &bases,
+                &moves_system,
+                operators,
+                strict,
+                normalize,
+            )?;
+            Repl::new(Model::Debug(Box::new(inputs)), normalize).run()?;
+            None
+        }
+        Some(Commands::Repl { command: None }) => {
+            let (frontend, sub) = frontend_matches(registry, matches.subcommand_matches("repl"))?;
+            let spec = frontend.spec(&frontend_inputs(frontend, sub), None)?;
+            Repl::new(Model::Spec(spec), normalize).run()?;
+            None
+        }
+        Some(Commands::Bench { runs, warmup, csv }) => {
+            let (frontend, sub) = frontend_matches(registry, matches.subcommand_matches("bench"))?;
+            let input_flags = InputFlags {
+                normalize,
+                checkpoint: None,
+            };
+            let mut measurements = Vec::new();
+            if csv {
+                println!("{}", bench::CSV_HEADER);
+            }
+            // Each task is printed once measured, since a suite may take long
+            for task in bench::tasks(&frontend_inputs(frontend, sub))? {
+                let task = task.iter().map(|path| path.as_path()).collect::<Vec<_>>();
+                let measurement = bench::bench(
+                    frontend,
+                    &task,
+                    &input_flags,
+                    warmup as usize,
+                    runs as usize,
+                )?;
+                match format {
+                    _ if csv => println!("{}", measurement.csv()),
+                    Format::Text => println!("{}", measurement),
+                    Format::Json => measurements.push(measurement.json()),
+                }
+            }
+            if !csv && format == Format::Json {
+                println!("{}", json!({ "bench": measurements }));
+            }
+            None
+        }
+    };
+    Ok(winner)
+}
+
+/// Prints the summary of the inputs, and their warnings as the validation
+/// does.
+fn print_lint(mut lint: LintOutput, strict: bool, format: &Format) -> Result<(), LcsfeError> {
+    let warnings = std::mem::take(&mut lint.warnings);
+    report(
+        Validation {
+            errors: vec![],
+            warnings: warnings.clone(),
+        },
+        strict,
+    )?;
+    match format {
+        Format::Text => println!("{}", lint),
+        Format::Json => println!(
+            "{}",
+            json!({
+                "summary": lint
+                    .summary
+                    .into_iter()
+                    .map(|(description, value)| (
+                        description.to_lowercase().replace(' ', "_"),
+                        json!(value)
+                    ))
+                    .collect::<serde_json::Map<_, _>>(),
+                "warnings": warnings,
+            })
+        ),
+    }
+    Ok(())
+}
+
+fn print_message(message: &str, format: &Format) {
+    match format {
+        Format::Text => println!("{}", message),
+        Format::Json => println!("{}", json!({ "message": message })),
+    }
+}
+
+/// Prints the outcome of the preprocessing, which in JSON is part of the
+/// final document.
+fn print_preproc(preproc: &PreProcOutput, explain: bool, format: &Format) {
+    match format {
+        Format::Text if explain => preproc.print_explain(),
+        Format::Text => println!("{}", preproc),
+        Format::Json => {}
+    }
+}
+
+fn print_verification(
+    result: &VerificationOutput,
+    preproc: Option<&PreProcOutput>,
+    query: serde_json::Value,
+    format: &Format,
+) {
+    match format {
+        Format::Text => println!("{}", result),
+        Format::Json => println!("{}", json_output(query, preproc, result)),
+    }
+}
+
+/// Builds the flags for the local algorithm; checkpointing is enabled only
+/// when a checkpoint is either saved or resumed, and it is bound to the
+/// content of the `inputs` files and to the `options` they are read with, see
+/// `hash_inputs`.
+fn input_flags(
+    normalize: bool,
+    save: Option<std::path::PathBuf>,
+    resume: Option<std::path::PathBuf>,
+    every: Duration,
+    inputs: &[&std::path::Path],
+    options: &[String],
+) -> Result<InputFlags, LcsfeError> {
+    let checkpoint = if save.is_some() || resume.is_some() {
+        Some(CheckpointFlags {
+            save,
+            resume,
+            every,
+            inputs: lcsfe_common::hash_inputs(inputs, normalize, options)?,
+        })
+    } else {
+        None
+    };
+    Ok(InputFlags {
+        normalize,
+        checkpoint,
+    })
+}
+
+/// Fails on the errors found by the validation, or on the warnings as well if
+/// `strict` is enabled, otherwise prints the warnings.
+fn report(validation: Validation, strict: bool) -> Result<(), LcsfeError> {
+    for warning in validation.into_result(strict)? {
+        eprintln!("Warning: {}", warning);
+    }
+    Ok(())
+}
+
+/// Parses an argument of the form `NAME=PATH`.
+fn named_path(s: &str) -> Result<(String, std::path::PathBuf), String> {
+    match s.split_once('=') {
+        Some((name, path)) if !name.is_empty() => Ok((name.to_owned(), path.into())),
+        _ => Err(format!("expected NAME=PATH, found {}", s)),
+    }
+}
+
+fn print_results(
+    results: &dyn SpecOutput,
+    preproc: PreProcOutput,
+    explain: bool,
+    input_flags: InputFlags,
+    query: serde_json::Value,
+    format: &Format,
+) -> Result<Player, LcsfeError> {
+    print_preproc(&preproc, explain, format);
+
+    let result = results.verify(&input_flags, &preproc)?;
+    print_verification(&result, Some(&preproc), query, format);
+    Ok(result.verdict.winner)
+}
@@ -0,0 +1,414 @@
+//! An interactive session on a model which is loaded once, e.g. a transition
+//! system, and then queried many times. The preprocessing is computed on the
+//! first query, and computed again only when the property or the
+//! normalization changes.
+
+use std::io::{BufRead, IsTerminal, Write};
+use std::time::Instant;
+
+use lcsfe_algorithm::{
+    algorithm::{LocalAlgorithm, Query},
+    ast::fixpoint_system::alternation_depth,
+};
+use lcsfe_common::{
+    InputFlags, LcsfeError, PreProcOutput, SpecOutput, Verdict, VerdictQuery, VerificationOutput,
+};
+
+use crate::debug::DebugInputs;
+
+const HELP: &str = "\
+Commands:
+  check <POSITION> [FORMULA]  Verifies the property from a position, e.g. a node or a state,
+                              after replacing the formula with FORMULA, if given
+  check <ELEMENT> <INDEX>     Verifies whether the element of the basis is below the solution
+                              of the INDEX-th equation, for the inputs of the `debug` command
+  formula <FORMULA>           Replaces the formula, which is parsed against the loaded model
+  positions                   Prints the positions which can be queried
+  explain                     Prints the system of fixpoint equations and the moves
+  stats                       Prints statistics about the solved system
+  normalize [on|off]          Sets or toggles the normalization of the system
+  help                        Prints this message
+  quit                        Ends the session";
+
+/// The model of a session.
+pub enum Model {
+    /// The verification task of a frontend
+    Spec(Box<dyn SpecOutput>),
+    /// The inputs of the `debug` command
+    Debug(Box<DebugInputs>),
+}
+
+pub struct Repl {
+    model: Model,
+    normalize: bool,
+    /// Missing until the first query, and whenever the property or the
+    /// normalization changes
+    pre_proc: Option<PreProcOutput>,
+}
+
+impl Repl {
+    pub fn new(model: Model, normalize: bool) -> Repl {
+        Repl {
+            model,
+            normalize,
+            pre_proc: None,
+        }
+    }
+
+    /// Reads commands from stdin until it ends or `quit` is read. The errors
+    /// of a command are printed, and do not end the session.
+    pub fn run(&mut self) -> Result<(), LcsfeError> {
+        let interactive = std::io::stdin().is_terminal();
+        self.session(std::io::stdin().lock(), &mut std::io::stdout(), interactive)
+    }
+
+    /// As `run`, reading the commands from `input` and printing their output
+    /// to `out`, with a prompt if `interactive`.
+    fn session(
+        &mut self,
+        input: impl BufRead,
+        out: &mut dyn Write,
+        interactive: bool,
+    ) -> Result<(), LcsfeError> {
+        if interactive {
+            writeln!(out, "Type `help` for the list of commands.")?;
+        }
+        let mut lines = input.lines();
+        loop {
+            if interactive {
+                write!(out, "lcsfe> ")?;
+                out.flush()?;
+            }
+            let line = match lines.next() {
+                Some(line) => line?,
+                None => return Ok(()),
+            };
+            let (command, args) = match line.trim().split_once(char::is_whitespace) {
+                Some((command, args)) => (command, args.trim()),
+                None => (line.trim(), ""),
+            };
+            let result = match command {
+                "" => Ok(()),
+                "quit" | "exit" => return Ok(()),
+                "help" => writeln!(out, "{}", HELP).map_err(LcsfeError::from),
+                "check" => self.check(args, out),
+                "formula" => self.set_formula(args),
+                "positions" => self.positions(out),
+                "explain" => pre_proc(&mut self.model, self.normalize, &mut self.pre_proc, out)
+                    .and_then(|pre_proc| Ok(pre_proc.write_explain(out)?)),
+                "stats" => self.stats(out),
+                "normalize" => self.set_normalize(args, out),
+                _ => Err(LcsfeError::Query(format!(
+                    "Unknown command {}, type `help` for the list of commands",
+                    command
+                ))),
+            };
+            if let Err(e) = result {
+                eprintln!("{}", e);
+            }
+        }
+    }
+
+    fn flags(&self) -> InputFlags {
+        InputFlags {
+            normalize: self.normalize,
+            checkpoint: None,
+        }
+    }
+
+    fn check(&mut self, args: &str, out: &mut dyn Write) -> Result<(), LcsfeError> {
+        let (position, rest) = match args.split_once(char::is_whitespace) {
+            Some((position, rest)) => (position, rest.trim()),
+            None => (args, ""),
+        };
+        if position.is_empty() {
+            return Err(LcsfeError::Query("Missing position".to_string()));
+        }
+        if !rest.is_empty() && matches!(self.model, Model::Spec(_)) {
+            self.set_formula(rest)?;
+        }
+        let flags = self.flags();
+        let pre_proc = pre_proc(&mut self.model, self.normalize, &mut self.pre_proc, out)?;
+        let result = match &self.model {
+            Model::Spec(spec) => spec.verify_at(&flags, pre_proc, position)?,
+            Model::Debug(inputs) => {
+                let index = rest.parse::<usize>().map_err(|_| {
+                    LcsfeError::Query(format!(
+                        "Expected the index of an equation, found {:?}",
+                        rest
+                    ))
+                })?;
+                check_debug(inputs, pre_proc, &flags, position.to_owned(), index)?
+            }
+        };
+        writeln!(out, "{}", result)?;
+        Ok(())
+    }
+
+    fn set_formula(&mut self, src: &str) -> Result<(), LcsfeError> {
+        match &mut self.model {
+            Model::Spec(spec) => spec.set_formula(src)?,
+            Model::Debug(_) => {
+                return Err(LcsfeError::Query(
+                    "The inputs of the `debug` command have no formula".to_string(),
+                ))
+            }
+        }
+        self.pre_proc = None;
+        Ok(())
+    }
+
+    fn positions(&self, out: &mut dyn Write) -> Result<(), LcsfeError> {
+        let positions = match &self.model {
+            Model::Spec(spec) => spec.positions(),
+            Model::Debug(inputs) => (1..=inputs.problem.original().len())
+                .map(|i| i.to_string())
+                .collect(),
+        };
+        writeln!(out, "{}", positions.join(" "))?;
+        Ok(())
+    }
+
+    fn stats(&mut self, out: &mut dyn Write) -> Result<(), LcsfeError> {
+        let normalize = self.normalize;
+        let pre_proc = pre_proc(&mut self.model, normalize, &mut self.pre_proc, out)?;
+        let statistics = pre_proc.statistics();
+        writeln!(out, "Equations: {}", statistics.equations)?;
+        writeln!(out, "Basis size: {}", statistics.basis_size)?;
+        writeln!(
+            out,
+            "Alternation depth: {}",
+            alternation_depth(&pre_proc.fix_system)
+        )?;
+        writeln!(out, "Normalized: {}", if normalize { "yes" } else { "no" })?;
+        writeln!(
+            out,
+            "Preprocessing took: {} sec.",
+            pre_proc.preproc_time.as_secs_f32()
+        )?;
+        Ok(())
+    }
+
+    fn set_normalize(&mut self, args: &str, out: &mut dyn Write) -> Result<(), LcsfeError> {
+        self.normalize = match args {
+            "" => !self.normalize,
+            "on" => true,
+            "off" => false,
+            _ => {
+                return Err(LcsfeError::Query(format!(
+                    "Expected on or off, found {}",
+                    args
+                )))
+            }
+        };
+        self.pre_proc = None;
+        writeln!(
+            out,
+            "Normalization is {}",
+            if self.normalize { "on" } else { "off" }
+        )?;
+        Ok(())
+    }
+}
+
+/// The preprocessing of the model, which is computed, reported to `out` and
+/// stored in `cache` if missing.
+fn pre_proc<'a>(
+    model: &mut Model,
+    normalize: bool,
+    cache: &'a mut Option<PreProcOutput>,
+    out: &mut dyn Write,
+) -> Result<&'a PreProcOutput, LcsfeError> {
+    let pre_proc = match cache.take() {
+        Some(pre_proc) => pre_proc,
+        None => {
+            let pre_proc = match model {
+                Model::Spec(spec) => spec.pre_proc(&InputFlags {
+                    normalize,
+                    checkpoint: None,
+                })?,
+                Model::Debug(inputs) => inputs.pre_proc(normalize)?,
+            };
+            writeln!(out, "{}", pre_proc)?;
+            pre_proc
+        }
+    };
+    Ok(cache.insert(pre_proc))
+}
+
+/// Verifies whether `element` is below the solution of the `position`-th
+/// equation, as the `debug` command does.
+fn check_debug(
+    inputs: &DebugInputs,
+    pre_proc: &PreProcOutput,
+    flags: &InputFlags,
+    element: String,
+    position: usize,
+) -> Result<VerificationOutput, LcsfeError> {
+    let (var_name, index) = inputs.index(position)?;
+    let mut elements = inputs.elements(position, &[element])?;
+
+    let algorithm = LocalAlgorithm {
+        fix_system: &pre_proc.fix_system,
+        symbolic_moves: &pre_proc.moves,
+        up_to: None,
+    };
+    let query = VerdictQuery {
+        basis_elements: elements.clone(),
+        index,
+        variable: pre_proc.fix_system[index].var.to_owned(),
+        original: var_name,
+    };
+    let start = Instant::now();
+    let winner = if elements.len() == 1 {
+        lcsfe_common::local_check(&algorithm, elements.remove(0), index, flags)?
+    } else {
+        algorithm.check_query(&Query::Below(elements, index))?
+    };
+    let algorithm_time = start.elapsed();
+
+    let verdict = Verdict {
+        winner,
+        query,
+        evidence: None,
+        statistics: Some(pre_proc.statistics()),
+    };
+    Ok(VerificationOutput {
+        algorithm_time,
+        result: verdict.to_string(),
+        verdict,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Model, Repl};
+    use crate::debug::{Bases, DebugInputs};
+    use lcsfe_mu_ald::MuAld;
+
+    /// The states 0, 1 and 2, with the labels `a` and `b`.
+    const LTS: &str = "des (0,3,3)\n(0,\"a\",1)\n(1,\"b\",2)\n(2,\"b\",2)\n";
+
+    fn mu_ald(formula: &str) -> Repl {
+        let spec = MuAld::from_source(LTS, formula, "0".into()).unwrap();
+        Repl::new(Model::Spec(Box::new(spec)), false)
+    }
+
+    /// Runs the commands, which are not interactive, and returns the output.
+    fn session(repl: &mut Repl, commands: &str) -> String {
+        let mut out = Vec::new();
+        repl.session(commands.as_bytes(), &mut out, false).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// The lines of the verdicts, without the timings.
+    fn results(out: &str) -> Vec<&str> {
+        out.lines()
+            .filter_map(|line| line.strip_prefix("Result: "))
+            .collect()
+    }
+
+    /// Unknown commands and wrong arguments are reported without ending the
+    /// session, which ends at `quit`.
+    #[test]
+    fn session_commands() {
+        let mut repl = mu_ald("mu x. <a>tt");
+        let out = session(
+            &mut repl,
+            "help\n\n  positions  \nfoo\ncheck\nnormalize maybe\nnormalize on\nquit\npositions\n",
+        );
+        assert!(out.starts_with(super::HELP));
+        assert_eq!(out.lines().filter(|line| *line == "0 1 2").count(), 1);
+        assert!(out.ends_with("Normalization is on\n"));
+        assert!(repl.normalize);
+        assert!(repl.pre_proc.is_none());
+    }
+
+    /// Changing the normalization discards the preprocessing, which is
+    /// computed again with the new flag by the next query.
+    #[test]
+    fn normalize_invalidates_pre_proc() {
+        let mut repl = mu_ald("mu x. <a>tt");
+        let out = session(&mut repl, "check 0\nstats\n");
+        assert_eq!(results(&out), ["The property is satisfied from state 0"]);
+        assert!(out.contains("Normalized: no"));
+        assert!(repl.pre_proc.as_ref().unwrap().var_map.is_empty());
+
+        let out = session(&mut repl, "normalize\n");
+        assert_eq!(out, "Normalization is on\n");
+        assert!(repl.pre_proc.is_none());
+
+        let out = session(&mut repl, "check 0\nstats\n");
+        assert_eq!(results(&out), ["The property is satisfied from state 0"]);
+        assert!(out.contains("Normalized: yes"));
+        assert!(!repl.pre_proc.as_ref().unwrap().var_map.is_empty());
+
+        session(&mut repl, "normalize off\n");
+        assert!(!repl.normalize);
+        assert!(repl.pre_proc.is_none());
+    }
+
+    /// The formula of `check <state> <formula>` is parsed against the labels
+    /// of the transition system, and it replaces the property only if it is
+    /// valid.
+    #[test]
+    fn check_parses_formula_against_labels() {
+        let mut repl = mu_ald("mu x. <a>tt");
+        let out = session(
+            &mut repl,
+            "check 0\ncheck 0 mu x. <b>tt\ncheck 0\ncheck 0 mu x. <c>tt\n\
+             check 1\ncheck 0 nu x. [a]ff\ncheck 3\n",
+        );
+        assert_eq!(
+            results(&out),
+            [
+                "The property is satisfied from state 0",
+                "The property is not satisfied from state 0",
+                "The property is not satisfied from state 0",
+                "The property is satisfied from state 1",
+                "The property is not satisfied from state 0",
+            ]
+        );
+    }
+
+    /// For the inputs of the `debug` command, `check` takes an element of
+    /// the basis and the position of an equation, starting from 1.
+    #[test]
+    fn check_debug_inputs() {
+        let path = |f: &str| {
+            std::path::PathBuf::from(format!(
+                "{}/../../tests/example_01/{}",
+                env!("CARGO_MANIFEST_DIR"),
+                f
+            ))
+        };
+        let bases = Bases {
+            default: path("basis"),
+            named: Vec::new(),
+            lattice: false,
+        };
+        let inputs = DebugInputs::load(
+            &path("arity"),
+            &path("fix_system"),
+            &bases,
+            &path("symbolic"),
+            false,
+            false,
+            false,
+        )
+        .unwrap();
+        let mut repl = Repl::new(Model::Debug(Box::new(inputs)), false);
+        let out = session(
+            &mut repl,
+            "positions\ncheck {b} 1\ncheck {a} 1\ncheck {b} x\ncheck {f} 1\nformula tt\n",
+        );
+        assert!(out.starts_with("1 2\n"));
+        assert_eq!(
+            results(&out),
+            [
+                "The winner from ({b}, x_1) is the existantial player",
+                "The winner from ({a}, x_1) is the universal player",
+            ]
+        );
+    }
+}
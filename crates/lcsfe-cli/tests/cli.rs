@@ -0,0 +1,224 @@
+//! Runs the binary on the examples of the repository, checking its exit code,
+//! i.e. 0 if the property holds, 1 if it does not, and 2 on errors, and the
+//! documents it prints.
+
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+use serde_json::{json, Value};
+
+/// Runs the binary from the root of the repository, so that the paths of the
+/// examples are relative to it.
+fn lcsfe(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_lcsfe-cli"))
+        .args(args)
+        .current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../.."))
+        .output()
+        .unwrap()
+}
+
+fn exit_code(args: &[&str]) -> i32 {
+    lcsfe(args).status.code().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn json_stdout(args: &[&str]) -> Value {
+    let output = lcsfe(args);
+    assert!(output.status.success(), "{:?}", output);
+    serde_json::from_str(&stdout(&output)).unwrap()
+}
+
+/// A directory for the inputs written by a test, which is emptied first.
+fn scratch(test: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(test);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// In `tests/parity_games/test_03.gm` player 0 wins from America, Asia and
+/// Australia, and player 1 from Africa and Antarctica.
+#[test]
+fn pg_exit_codes() {
+    let game = "tests/parity_games/test_03.gm";
+    assert_eq!(exit_code(&["pg", game, "America"]), 0);
+    assert_eq!(exit_code(&["pg", game, "Africa"]), 1);
+    assert_eq!(exit_code(&["-n", "pg", game, "Asia", "Australia"]), 0);
+    assert_eq!(exit_code(&["pg", game, "--all"]), 1);
+    assert_eq!(
+        exit_code(&["pg", game, "America", "--solver", "zielonka"]),
+        0
+    );
+    assert_eq!(
+        exit_code(&["pg", game, "Africa", "--solver", "zielonka"]),
+        1
+    );
+    assert_eq!(exit_code(&["pg", game, "Europe"]), 2);
+    assert_eq!(exit_code(&["pg", "tests/parity_games/missing.gm", "0"]), 2);
+}
+
+#[test]
+fn mu_ald_exit_codes() {
+    let dir = scratch("mu_ald_exit_codes");
+    let formula = |name: &str, src: &str| {
+        let path = dir.join(name);
+        std::fs::write(&path, src).unwrap();
+        path.display().to_string()
+    };
+    let lts = "tests/example_mucalc/bridge-referee.aut";
+    let liveness = "tests/example_mucalc/deadlock-liveness";
+    let never = formula("never", "mu x. ff");
+    let unknown = formula("unknown", "mu x. <unknown>tt");
+
+    assert_eq!(exit_code(&["mu-ald", lts, liveness, "5"]), 0);
+    assert_eq!(exit_code(&["-n", "mu-ald", lts, liveness, "5"]), 0);
+    assert_eq!(exit_code(&["mu-ald", lts, &never, "5"]), 1);
+    assert_eq!(exit_code(&["mu-ald", lts, &unknown, "5"]), 2);
+    assert_eq!(exit_code(&["mu-ald", lts, liveness, "1000"]), 2);
+}
+
+/// The summary of the lint keeps the types of its values, e.g. a list of
+/// states is an array of numbers even when it is empty, and the warnings are
+/// errors with `--strict`.
+#[test]
+fn lint_json() {
+    assert_eq!(
+        json_stdout(&[
+            "--format",
+            "json",
+            "lint",
+            "pg",
+            "tests/parity_games/test_03.gm"
+        ]),
+        json!({
+            "summary": {
+                "nodes": 5,
+                "edges": 11,
+                "greatest_priority": 8,
+                "equations": 5,
+                "alternation_depth": 4,
+            },
+            "warnings": [],
+        })
+    );
+
+    let lint = json_stdout(&[
+        "--format",
+        "json",
+        "lint",
+        "mu-ald",
+        "tests/example_mucalc/bridge-referee.aut",
+        "tests/example_mucalc/deadlock-liveness",
+    ]);
+    assert_eq!(lint["summary"]["states"], json!(102));
+    assert_eq!(lint["summary"]["unreachable_states"], json!([]));
+
+    let dir = scratch("lint_json");
+    let lts = dir.join("unreachable.aut");
+    let formula = dir.join("formula");
+    std::fs::write(
+        &lts,
+        "des (0,5,4)\n(0,\"a\",1)\n(1,\"a\",0)\n(2,\"a\",0)\n(3,\"b\",2)\n",
+    )
+    .unwrap();
+    std::fs::write(&formula, "mu x. <a>tt").unwrap();
+    let (lts, formula) = (lts.display().to_string(), formula.display().to_string());
+    let lint = json_stdout(&["--format", "json", "lint", "mu-ald", &lts, &formula]);
+    assert_eq!(lint["summary"]["unreachable_states"], json!([2, 3]));
+    assert_eq!(
+        lint["warnings"],
+        json!(["the header declares 5 transitions, but there are 4"])
+    );
+
+    let output = lcsfe(&[
+        "--strict", "--format", "json", "lint", "mu-ald", &lts, &formula,
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+    let error: Value = serde_json::from_str(&stdout(&output)).unwrap();
+    assert_eq!(error["error"]["kind"], json!("validation"));
+
+    let example = |f: &str| format!("tests/example_01/{}", f);
+    let lint = json_stdout(&[
+        "--format",
+        "json",
+        "lint",
+        "debug",
+        &example("arity"),
+        &example("fix_system"),
+        &example("basis"),
+        &example("symbolic"),
+    ]);
+    assert_eq!(
+        lint["summary"]["operators_used"],
+        json!(["box", "diamond", "p"])
+    );
+    assert_eq!(lint["summary"]["basis_size"], json!(5));
+}
+
+/// The bench command runs the task on each file of a directory, and reports
+/// the statistics of each phase, which are ordered, in every format.
+#[test]
+fn bench_statistics_and_csv() {
+    let dir = scratch("bench_statistics_and_csv");
+    for game in ["test_01.gm", "test_02.gm", "test_03.gm"] {
+        std::fs::copy(
+            format!(
+                "{}/../../tests/parity_games/{}",
+                env!("CARGO_MANIFEST_DIR"),
+                game
+            ),
+            dir.join(game),
+        )
+        .unwrap();
+    }
+    let dir = dir.display().to_string();
+    let ordered = |stats: &[f64]| stats.windows(2).all(|x| x[0] <= x[1]);
+
+    let output = lcsfe(&["bench", "-r", "3", "-w", "1", "--csv", "pg", &dir]);
+    assert_eq!(output.status.code(), Some(0));
+    let csv = stdout(&output);
+    let mut lines = csv.lines();
+    let header = lines.next().unwrap().split(',').collect::<Vec<_>>();
+    assert_eq!(header.len(), 15);
+    assert_eq!(header[..4], ["task", "holds", "runs", "preproc_min_sec"]);
+    let rows = lines
+        .map(|line| line.split(',').collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    assert_eq!(rows.len(), 3);
+    for (row, (game, holds)) in rows.iter().zip([
+        ("test_01.gm", "false"),
+        ("test_02.gm", "true"),
+        ("test_03.gm", "false"),
+    ]) {
+        assert_eq!(row.len(), header.len());
+        assert!(row[0].ends_with(game), "{:?}", row);
+        assert_eq!(row[1..3], [holds, "3"]);
+        for stats in [&row[3..6], &row[9..12]] {
+            let stats = stats.iter().map(|x| x.parse().unwrap()).collect::<Vec<_>>();
+            assert!(ordered(&stats), "{:?}", row);
+        }
+    }
+
+    let game = format!("{}/test_02.gm", dir);
+    let output = lcsfe(&["bench", "-r", "3", "-w", "0", "pg", &game]);
+    assert_eq!(output.status.code(), Some(0));
+    let text = stdout(&output);
+    assert!(text.starts_with(&format!("{}: the property holds, 3 runs\n", game)));
+    assert!(text.contains("\n  Preprocessing time: min "));
+    assert!(text.contains("\n  Solving time: min "));
+
+    let bench = json_stdout(&[
+        "--format", "json", "bench", "-r", "3", "-w", "0", "pg", &game,
+    ]);
+    let task = &bench["bench"][0];
+    assert_eq!(task["runs"], json!(3));
+    assert_eq!(task["holds"], json!(true));
+    for phase in ["preproc", "solve"] {
+        let time = &task[phase]["time"];
+        let stats = ["min", "median", "max"].map(|x| time[x].as_f64().unwrap());
+        assert!(ordered(&stats), "{}", task);
+    }
+}
@@ -0,0 +1,245 @@
+//! The preprocessing of a verification task, i.e. the translation of the
+//! inputs and the composition of the symbolic exists-moves, is stored on disk
+//! and reused by later runs on the same inputs, e.g. for another query.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use lcsfe_algorithm::algorithm::LocalAlgorithm;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Frontend, InputFlags, LcsfeError, PreProcOutput, SpecOutput, Verdict, VerdictQuery,
+    VerificationOutput,
+};
+
+/// A position which can be queried, along with where its verdict starts from
+/// in the preprocessed system.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    /// The name of the position in the input, e.g. of a node
+    pub name: String,
+    pub basis_element: String,
+    /// The index of the equation, after normalization if requested
+    pub index: usize,
+    /// The variable of the query, as returned by `SpecOutput::query_var`
+    pub var: String,
+}
+
+/// The positions of a verification task, which let the queries be answered
+/// from a stored preprocessing without reading the inputs, see
+/// `SpecOutput::locate`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Positions {
+    /// The position of the task built without a query, e.g. the initial state
+    /// of a transition system
+    pub default: String,
+    /// In the order of `SpecOutput::positions`
+    pub positions: Vec<Position>,
+}
+
+/// An entry of the cache, with the digest of the inputs it was computed from,
+/// which is checked on read.
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    inputs: String,
+    version: String,
+    positions: Positions,
+    pre_proc: PreProcOutput,
+}
+
+/// Builds the verification task of the `frontend` for the query, and
+/// preprocesses it. If a previous run on the same `inputs`, with the same
+/// flags, stored its preprocessing in `dir`, the task is built from the entry,
+/// without reading the inputs again, and otherwise the inputs are parsed and
+/// the entry is stored. An entry which cannot be read, e.g. one written by
+/// another version, is computed and stored again.
+pub fn cached_spec<'a>(
+    frontend: &'a dyn Frontend,
+    inputs: &[&Path],
+    query: Option<String>,
+    flags: &InputFlags,
+    dir: &Path,
+) -> Result<(Box<dyn SpecOutput + 'a>, PreProcOutput), LcsfeError> {
+    let (path, digest) = entry(frontend.name(), inputs, flags.normalize, dir)?;
+
+    let start = Instant::now();
+    if let Some(entry) = File::open(&path)
+        .ok()
+        .and_then(|file| serde_json::from_reader::<_, Entry>(BufReader::new(file)).ok())
+        .filter(|entry| entry.inputs == digest && entry.version == env!("CARGO_PKG_VERSION"))
+    {
+        let spec = CachedSpec::new(frontend, entry.positions, query)?;
+        let mut pre_proc = entry.pre_proc;
+        pre_proc.var = spec.query_var();
+        pre_proc.preproc_time = start.elapsed();
+        pre_proc.cached = true;
+        return Ok((Box::new(spec), pre_proc));
+    }
+
+    let spec = frontend.spec(inputs, query)?;
+    let pre_proc = spec.pre_proc(flags)?;
+    let entry = Entry {
+        inputs: digest,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        positions: spec.locate(flags, &pre_proc)?,
+        pre_proc,
+    };
+    std::fs::create_dir_all(dir).map_err(|e| LcsfeError::io(dir, e))?;
+    store(&path, &entry).map_err(|e| LcsfeError::io(&path, e))?;
+    Ok((spec, entry.pre_proc))
+}
+
+/// The file of the entry for the inputs, named after the SHA-256 digest of
+/// their content and of the flags which affect the preprocessing, which is
+/// returned as well.
+fn entry(
+    frontend: &str,
+    inputs: &[&Path],
+    normalize: bool,
+    dir: &Path,
+) -> Result<(PathBuf, String), LcsfeError> {
+    if inputs.iter().any(|path| crate::is_stdin(path)) {
+        return Err(LcsfeError::Query(
+            "The cache is not supported for inputs read from stdin".to_string(),
+        ));
+    }
+    let digest = crate::hash_inputs(inputs, normalize, &[])?;
+    Ok((dir.join(format!("{}-{}.json", frontend, digest)), digest))
+}
+
+/// Writes the entry to a temporary file first, so that an interrupted write,
+/// or a concurrent run, never leaves a partial entry.
+fn store(path: &Path, entry: &Entry) -> Result<(), std::io::Error> {
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    let mut writer = BufWriter::new(File::create(&tmp)?);
+    serde_json::to_writer(&mut writer, entry)?;
+    writer.flush()?;
+    std::fs::rename(tmp, path)
+}
+
+/// A verification task built from an entry of the cache, which answers the
+/// queries with the stored positions and describes the verdicts as its
+/// frontend.
+struct CachedSpec<'a> {
+    frontend: &'a dyn Frontend,
+    positions: Positions,
+    /// The index of the position of the query in `positions`
+    query: usize,
+}
+
+impl<'a> CachedSpec<'a> {
+    fn new(
+        frontend: &'a dyn Frontend,
+        positions: Positions,
+        query: Option<String>,
+    ) -> Result<CachedSpec<'a>, LcsfeError> {
+        let mut spec = CachedSpec {
+            frontend,
+            positions,
+            query: 0,
+        };
+        let query = query.unwrap_or_else(|| spec.positions.default.to_owned());
+        spec.query = spec.position(&query)?;
+        Ok(spec)
+    }
+
+    fn position(&self, name: &str) -> Result<usize, LcsfeError> {
+        self.positions
+            .positions
+            .iter()
+            .position(|x| x.name == name)
+            .ok_or_else(|| {
+                LcsfeError::Query(format!(
+                    "Cannot find {} with name {}",
+                    self.frontend.query().name,
+                    name
+                ))
+            })
+    }
+}
+
+impl SpecOutput for CachedSpec<'_> {
+    fn pre_proc(&self, _flags: &InputFlags) -> Result<PreProcOutput, LcsfeError> {
+        Err(LcsfeError::Query(
+            "The preprocessing of a task read from the cache cannot be computed again".to_string(),
+        ))
+    }
+
+    fn query_var(&self) -> String {
+        self.positions.positions[self.query].var.to_owned()
+    }
+
+    fn verify(
+        &self,
+        flags: &InputFlags,
+        pre_proc: &PreProcOutput,
+    ) -> Result<VerificationOutput, LcsfeError> {
+        let name = self.positions.positions[self.query].name.to_owned();
+        self.verify_at(flags, pre_proc, &name)
+    }
+
+    fn positions(&self) -> Vec<String> {
+        self.positions
+            .positions
+            .iter()
+            .map(|x| x.name.to_owned())
+            .collect()
+    }
+
+    fn locate(
+        &self,
+        _flags: &InputFlags,
+        _pre_proc: &PreProcOutput,
+    ) -> Result<Positions, LcsfeError> {
+        Ok(self.positions.clone())
+    }
+
+    fn verify_at(
+        &self,
+        flags: &InputFlags,
+        pre_proc: &PreProcOutput,
+        name: &str,
+    ) -> Result<VerificationOutput, LcsfeError> {
+        let position = &self.positions.positions[self.position(name)?];
+        let algo = LocalAlgorithm {
+            fix_system: &pre_proc.fix_system,
+            symbolic_moves: &pre_proc.moves,
+            up_to: None,
+        };
+
+        let start = Instant::now();
+        let winner = crate::local_check(
+            &algo,
+            position.basis_element.to_owned(),
+            position.index,
+            flags,
+        )?;
+        let algorithm_time = start.elapsed();
+
+        let verdict = Verdict {
+            winner,
+            query: VerdictQuery {
+                basis_elements: vec![position.basis_element.to_owned()],
+                index: position.index,
+                variable: pre_proc.fix_system[position.index].var.to_owned(),
+                original: position.name.to_owned(),
+            },
+            evidence: None,
+            statistics: Some(pre_proc.statistics()),
+        };
+        Ok(VerificationOutput {
+            algorithm_time,
+            result: self.describe(&verdict),
+            verdict,
+        })
+    }
+
+    fn describe(&self, verdict: &Verdict) -> String {
+        self.frontend.describe(verdict)
+    }
+}
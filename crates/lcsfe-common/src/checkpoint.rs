@@ -0,0 +1,96 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use lcsfe_algorithm::algorithm::{Checkpoint, LocalAlgorithm, Player};
+use sha2::{Digest, Sha256};
+
+use crate::{InputFlags, LcsfeError};
+
+pub struct CheckpointFlags {
+    /// The file the state of the local algorithm is periodically saved to
+    pub save: Option<PathBuf>,
+    /// A file previously written by a run on the same inputs, from which the
+    /// local algorithm resumes
+    pub resume: Option<PathBuf>,
+    /// How often the state of the local algorithm is saved
+    pub every: Duration,
+    /// A fingerprint of the inputs, as returned by `hash_inputs`
+    pub inputs: String,
+}
+
+/// Returns a fingerprint of the content of the files in `paths` and of the
+/// options which affect the system being solved, i.e. `normalize` and the
+/// `options` which tell how the files are read, e.g. as a lattice, used to
+/// make sure a checkpoint is resumed only against the inputs it was taken
+/// for. It is a SHA-256 digest, in hexadecimal, since resuming against other
+/// inputs would silently give a wrong verdict.
+pub fn hash_inputs(
+    paths: &[&Path],
+    normalize: bool,
+    options: &[String],
+) -> Result<String, LcsfeError> {
+    let mut hasher = Sha256::new();
+    for path in paths {
+        if crate::is_stdin(path) {
+            return Err(LcsfeError::Query(
+                "Checkpoints are not supported for inputs read from stdin".to_string(),
+            ));
+        }
+        let content = std::fs::read(path).map_err(|e| LcsfeError::io(path, e))?;
+        hasher.update((content.len() as u64).to_le_bytes());
+        hasher.update(&content);
+    }
+    hasher.update([normalize as u8]);
+    for option in options {
+        hasher.update((option.len() as u64).to_le_bytes());
+        hasher.update(option);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Runs the local algorithm from the basis element `b` and the equation with
+/// index `i`, saving and resuming its state as requested by `flags`.
+pub fn local_check(
+    algo: &LocalAlgorithm,
+    b: String,
+    i: usize,
+    flags: &InputFlags,
+) -> Result<Player, LcsfeError> {
+    let checkpoint = match &flags.checkpoint {
+        Some(checkpoint) => checkpoint,
+        None => return Ok(algo.local_check(b, i)?),
+    };
+
+    let resume = match &checkpoint.resume {
+        Some(path) => Some(serde_json::from_reader::<_, Checkpoint>(BufReader::new(
+            LcsfeError::open(path)?,
+        ))?),
+        None => None,
+    };
+
+    algo.local_check_checkpointed(
+        b,
+        i,
+        &checkpoint.inputs,
+        resume,
+        checkpoint.every,
+        &mut |state| match &checkpoint.save {
+            Some(path) => save(path, state),
+            None => Ok(()),
+        },
+    )
+    .map_err(LcsfeError::from)
+}
+
+/// Writes the checkpoint to a temporary file first, so that an interrupted
+/// write never replaces a valid checkpoint.
+fn save(path: &Path, state: &Checkpoint) -> Result<(), Box<dyn std::error::Error>> {
+    let tmp = path.with_extension("tmp");
+    serde_json::to_writer(BufWriter::new(File::create(&tmp)?), state)?;
+    std::fs::rename(tmp, path)?;
+    Ok(())
+}
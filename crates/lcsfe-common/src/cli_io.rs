@@ -1,40 +1,81 @@
 use rustc_hash::FxHashMap as HashMap;
 use std::{fmt::Display, time::Duration};
 
-use lcsfe_algorithm::ast::{
-    fixpoint_system::FixEq, symbolic_moves_composed::SymbolicExistsMoves,
+use crate::cache::Positions;
+use crate::checkpoint::CheckpointFlags;
+use crate::{LcsfeError, Statistics, Verdict};
+
+use lcsfe_algorithm::{
+    algorithm::Player,
+    ast::{fixpoint_system::FixEq, symbolic_moves_composed::SymbolicExistsMoves},
 };
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 
+/// The system of fixpoint equations of a verification task, along with the
+/// symbolic exists-moves composed with it. It can be stored, see
+/// `cached_spec`, and reused for other queries on the same inputs.
+#[derive(Serialize, Deserialize)]
 pub struct PreProcOutput {
     pub moves: SymbolicExistsMoves,
     pub fix_system: Vec<FixEq>,
     pub var_map: HashMap<String, String>,
+    /// The variable of the query, as returned by `SpecOutput::query_var`
     pub var: String,
     pub preproc_time: Duration,
+    /// Whether it was read from the cache rather than computed, in which case
+    /// `preproc_time` is the time it took to read it
+    #[serde(skip)]
+    pub cached: bool,
 }
 
 impl PreProcOutput {
     pub fn print_explain(&self) {
-        println!("Fixpoint system:\n");
-        self.fix_system.iter().for_each(|x| println!("{};", x));
+        self.write_explain(&mut std::io::stdout())
+            .expect("failed printing to stdout")
+    }
+
+    /// As `print_explain`, to `out`.
+    pub fn write_explain(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        writeln!(out, "Fixpoint system:\n")?;
+        for x in &self.fix_system {
+            writeln!(out, "{};", x)?;
+        }
+
+        writeln!(out, "\nSymbolic exists-moves:\n\n{}", self.moves)?;
+        writeln!(out, "\n{}", self)
+    }
 
-        println!("\nSymbolic exists-moves:\n\n{}", self.moves);
-        println!("\n{}", self)
+    pub fn statistics(&self) -> Statistics {
+        Statistics {
+            equations: self.fix_system.len(),
+            basis_size: self.moves.basis_len(),
+        }
     }
 }
 
 impl Display for PreProcOutput {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "Preprocessing took: {} sec.",
-            self.preproc_time.as_secs_f32()
-        )
+        if self.cached {
+            write!(
+                f,
+                "Preprocessing read from the cache in: {} sec.",
+                self.preproc_time.as_secs_f32()
+            )
+        } else {
+            write!(
+                f,
+                "Preprocessing took: {} sec.",
+                self.preproc_time.as_secs_f32()
+            )
+        }
     }
 }
 
 pub struct VerificationOutput {
     pub algorithm_time: Duration,
+    pub verdict: Verdict,
+    /// The verdict in the wording of the frontend
     pub result: String,
 }
 
@@ -50,16 +91,304 @@ impl Display for VerificationOutput {
     }
 }
 
+/// The results of many queries, i.e. positions, which share the
+/// preprocessing.
+pub struct BatchOutput {
+    /// What the positions are, e.g. `node`
+    pub query: String,
+    pub results: Vec<VerificationOutput>,
+}
+
+impl BatchOutput {
+    /// The number of positions from which the property holds.
+    pub fn satisfied(&self) -> usize {
+        self.results.iter().filter(|x| x.verdict.holds()).count()
+    }
+}
+
+impl Display for BatchOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows = self
+            .results
+            .iter()
+            .map(|x| {
+                [
+                    x.verdict.query.original.to_owned(),
+                    if x.verdict.holds() { "yes" } else { "no" }.to_string(),
+                    format!("{:.6}", x.algorithm_time.as_secs_f64()),
+                    x.result.to_owned(),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let header = [
+            self.query.to_owned(),
+            "satisfied".to_string(),
+            "time (sec.)".to_string(),
+            "result".to_string(),
+        ];
+        let widths = (0..3)
+            .map(|i| {
+                rows.iter()
+                    .map(|row| row[i].len())
+                    .chain(std::iter::once(header[i].len()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+        for row in std::iter::once(&header).chain(rows.iter()) {
+            writeln!(
+                f,
+                "{:w0$}  {:w1$}  {:w2$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2]
+            )?;
+        }
+        write!(
+            f,
+            "The property holds from {} of {} positions",
+            self.satisfied(),
+            self.results.len()
+        )
+    }
+}
+
+/// A summary of the inputs of a verification task, computed without solving
+/// it, along with the likely mistakes found in the inputs.
+#[derive(Default)]
+pub struct LintOutput {
+    /// Pairs of a description and a value, in order
+    pub summary: Vec<(String, LintValue)>,
+    pub warnings: Vec<String>,
+}
+
+/// A value of the summary of the inputs, which keeps its type in the JSON
+/// output: a number, or an array of numbers or of names.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum LintValue {
+    Number(u64),
+    Numbers(Vec<u64>),
+    Names(Vec<String>),
+}
+
+impl From<usize> for LintValue {
+    fn from(n: usize) -> LintValue {
+        LintValue::Number(n as u64)
+    }
+}
+
+impl From<u32> for LintValue {
+    fn from(n: u32) -> LintValue {
+        LintValue::Number(n.into())
+    }
+}
+
+impl From<Vec<u32>> for LintValue {
+    fn from(ns: Vec<u32>) -> LintValue {
+        LintValue::Numbers(ns.into_iter().map(u64::from).collect())
+    }
+}
+
+impl From<Vec<String>> for LintValue {
+    fn from(names: Vec<String>) -> LintValue {
+        LintValue::Names(names)
+    }
+}
+
+impl Display for LintValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let items = match self {
+            LintValue::Number(n) => return write!(f, "{}", n),
+            LintValue::Numbers(ns) => ns.iter().map(|n| n.to_string()).collect::<Vec<_>>(),
+            LintValue::Names(names) => names.clone(),
+        };
+        if items.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", items.join(", "))
+        }
+    }
+}
+
+impl LintOutput {
+    pub fn push(&mut self, description: &str, value: impl Into<LintValue>) {
+        self.summary.push((description.to_owned(), value.into()))
+    }
+}
+
+impl Display for LintOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .summary
+            .iter()
+            .map(|(description, value)| format!("{}: {}", description, value))
+            .collect::<Vec<_>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Describes a verification task as a JSON document, for scripts: the query,
+/// as given by the caller, the verdict, the timings, and the map from the
+/// original variables to those of the normalized system, if any.
+pub fn json_output(
+    query: serde_json::Value,
+    pre_proc: Option<&PreProcOutput>,
+    verification: &VerificationOutput,
+) -> serde_json::Value {
+    let verdict = &verification.verdict;
+    let winner = match verdict.winner {
+        Player::Eve => "eve",
+        Player::Adam => "adam",
+    };
+    let mut output = json!({
+        "query": query,
+        "satisfied": verdict.holds(),
+        "winner": winner,
+        "result": verification.result,
+        "position": {
+            "basis_elements": verdict.query.basis_elements,
+            "index": verdict.query.index,
+            "variable": verdict.query.variable,
+            "original": verdict.query.original,
+        },
+        "timings": {
+            "algorithm": verification.algorithm_time.as_secs_f64(),
+        },
+    });
+    if let Some(statistics) = &verdict.statistics {
+        output["statistics"] = json!({
+            "equations": statistics.equations,
+            "basis_size": statistics.basis_size,
+        });
+    }
+    if let Some(certificate) = &verdict.evidence {
+        output["certificate"] = json!(certificate.to_string());
+    }
+    if let Some(pre_proc) = pre_proc {
+        output["timings"]["preprocessing"] = json!(pre_proc.preproc_time.as_secs_f64());
+        output["cached"] = json!(pre_proc.cached);
+        output["variable_map"] = json!(pre_proc
+            .var_map
+            .iter()
+            .collect::<std::collections::BTreeMap<_, _>>());
+    }
+    output
+}
+
+/// Describes a batch of queries as a JSON document: an object for each
+/// query as `json_output` does, along with the shared preprocessing.
+pub fn json_batch_output(
+    pre_proc: Option<&PreProcOutput>,
+    batch: &BatchOutput,
+) -> serde_json::Value {
+    let mut output = json!({
+        "satisfied": batch.satisfied(),
+        "total": batch.results.len(),
+        "results": batch
+            .results
+            .iter()
+            .map(|x| json_output(json!({ batch.query.as_str(): x.verdict.query.original }), None, x))
+            .collect::<Vec<_>>(),
+    });
+    if let Some(pre_proc) = pre_proc {
+        let statistics = pre_proc.statistics();
+        output["timings"] = json!({ "preprocessing": pre_proc.preproc_time.as_secs_f64() });
+        output["cached"] = json!(pre_proc.cached);
+        output["statistics"] = json!({
+            "equations": statistics.equations,
+            "basis_size": statistics.basis_size,
+        });
+        output["variable_map"] = json!(pre_proc
+            .var_map
+            .iter()
+            .collect::<std::collections::BTreeMap<_, _>>());
+    }
+    output
+}
+
 pub struct InputFlags {
     pub normalize: bool,
+    /// If present, the local algorithm periodically saves its state, and
+    /// possibly resumes from a previous run
+    pub checkpoint: Option<CheckpointFlags>,
 }
 
 pub trait SpecOutput {
-    fn pre_proc(&self, flags: &InputFlags) -> Result<PreProcOutput, Box<dyn std::error::Error>>;
-    /// Execute the local algorithm and return the result wrapped in a string.
+    fn pre_proc(&self, flags: &InputFlags) -> Result<PreProcOutput, LcsfeError>;
+    /// What the query the task was built with is about in the system of
+    /// fixpoint equations, i.e. `PreProcOutput::var`, which is the only part
+    /// of the preprocessing which depends on the query.
+    fn query_var(&self) -> String;
+    /// Execute the local algorithm from the position the task was built
+    /// with, and return its verdict, along with its description given by
+    /// `describe`.
     fn verify(
         &self,
         flags: &InputFlags,
         pre_proc: &PreProcOutput,
-    ) -> Result<VerificationOutput, Box<dyn std::error::Error>>;
+    ) -> Result<VerificationOutput, LcsfeError>;
+    /// All the positions which can be queried, e.g. the nodes of a parity
+    /// game, in the order of the input.
+    fn positions(&self) -> Vec<String>;
+    /// Where each of `positions` starts from in the preprocessed system, so
+    /// that a stored preprocessing answers the queries without the inputs,
+    /// see `cached_spec`.
+    fn locate(&self, flags: &InputFlags, pre_proc: &PreProcOutput)
+        -> Result<Positions, LcsfeError>;
+    /// As `verify`, but from `position`, which must be one of `positions`.
+    fn verify_at(
+        &self,
+        flags: &InputFlags,
+        pre_proc: &PreProcOutput,
+        position: &str,
+    ) -> Result<VerificationOutput, LcsfeError>;
+    /// Verifies each of the positions, sharing the preprocessing.
+    fn verify_many(
+        &self,
+        flags: &InputFlags,
+        pre_proc: &PreProcOutput,
+        positions: &[String],
+    ) -> Result<Vec<VerificationOutput>, LcsfeError> {
+        positions
+            .iter()
+            .map(|position| self.verify_at(flags, pre_proc, position))
+            .collect()
+    }
+    /// Describes the verdict in the terms of the input, e.g. a player of a
+    /// parity game or a property of a transition system.
+    fn describe(&self, verdict: &Verdict) -> String {
+        verdict.to_string()
+    }
+    /// Replaces the property which is verified with the one in `src`, for
+    /// the frontends whose inputs include a formula, which is parsed against
+    /// the model already loaded, e.g. the labels of a transition system.
+    fn set_formula(&mut self, _src: &str) -> Result<(), LcsfeError> {
+        Err(LcsfeError::Query(
+            "The property of this frontend cannot be replaced".to_string(),
+        ))
+    }
+    /// Solves the task from `position` with one of the solvers of the
+    /// frontend, other than the local algorithm, e.g. Zielonka's algorithm
+    /// for parity games.
+    fn verify_with(&self, solver: &str, _position: &str) -> Result<VerificationOutput, LcsfeError> {
+        Err(LcsfeError::Query(format!("Unknown solver {}", solver)))
+    }
+    /// As `verify_with`, for each of the positions. The frontends whose
+    /// solvers are global, e.g. Zielonka's algorithm, solve the task once,
+    /// and report the time of the solver on the first position.
+    fn verify_many_with(
+        &self,
+        solver: &str,
+        positions: &[String],
+    ) -> Result<Vec<VerificationOutput>, LcsfeError> {
+        positions
+            .iter()
+            .map(|position| self.verify_with(solver, position))
+            .collect()
+    }
 }
@@ -0,0 +1,188 @@
+use std::{fmt, io, path::Path};
+
+use lcsfe_algorithm::{
+    algorithm::{CertificateError, CheckpointError, QueryError},
+    ast::{
+        lattice::LatticeError, operator_table::MovesError, symbolic_moves_composed::ComposeError,
+    },
+    parse::ParserError,
+    problem::ProblemError,
+    validation::ValidationError,
+};
+
+/// The errors reported by the frontends and the command line interface. The
+/// message of each variant is meant to be printed as is.
+#[derive(Debug)]
+pub enum LcsfeError {
+    /// An input could not be read, or an output could not be written
+    Io(io::Error),
+    /// An input does not follow its grammar
+    Parse(String),
+    /// The inputs are well formed but inconsistent with each other, e.g. a
+    /// symbolic exists-move mentions an element outside the basis
+    Validation(String),
+    /// The question asked does not make sense for the inputs, e.g. an
+    /// unknown node, state or basis element
+    Query(String),
+}
+
+impl LcsfeError {
+    /// The name of the variant, as reported in machine readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LcsfeError::Io(_) => "io",
+            LcsfeError::Parse(_) => "parse",
+            LcsfeError::Validation(_) => "validation",
+            LcsfeError::Query(_) => "query",
+        }
+    }
+
+    /// Reads the whole file, reporting its path if it cannot be read.
+    pub fn read_to_string(path: &Path) -> Result<String, LcsfeError> {
+        std::fs::read_to_string(path).map_err(|e| LcsfeError::io(path, e))
+    }
+
+    /// Opens the file, reporting its path if it cannot be opened.
+    pub fn open(path: &Path) -> Result<std::fs::File, LcsfeError> {
+        std::fs::File::open(path).map_err(|e| LcsfeError::io(path, e))
+    }
+
+    pub fn io(path: &Path, e: io::Error) -> LcsfeError {
+        LcsfeError::Io(io::Error::new(
+            e.kind(),
+            format!("{}: {}", path.display(), e),
+        ))
+    }
+}
+
+impl std::error::Error for LcsfeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LcsfeError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LcsfeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LcsfeError::Io(e) => write!(f, "I/O error: {}", e),
+            LcsfeError::Parse(details)
+            | LcsfeError::Validation(details)
+            | LcsfeError::Query(details) => write!(f, "{}", details),
+        }
+    }
+}
+
+impl From<io::Error> for LcsfeError {
+    fn from(e: io::Error) -> Self {
+        LcsfeError::Io(e)
+    }
+}
+
+impl From<ParserError> for LcsfeError {
+    fn from(e: ParserError) -> Self {
+        LcsfeError::Parse(e.to_string())
+    }
+}
+
+impl From<Vec<ParserError>> for LcsfeError {
+    fn from(errs: Vec<ParserError>) -> Self {
+        LcsfeError::Parse(
+            errs.iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+}
+
+impl From<serde_json::Error> for LcsfeError {
+    fn from(e: serde_json::Error) -> Self {
+        if e.is_io() {
+            LcsfeError::Io(e.into())
+        } else {
+            LcsfeError::Parse(format!("Parse error(s):\n{}", e))
+        }
+    }
+}
+
+impl From<ComposeError> for LcsfeError {
+    fn from(e: ComposeError) -> Self {
+        LcsfeError::Validation(e.to_string())
+    }
+}
+
+impl From<ValidationError> for LcsfeError {
+    fn from(e: ValidationError) -> Self {
+        LcsfeError::Validation(e.to_string())
+    }
+}
+
+impl From<LatticeError> for LcsfeError {
+    fn from(e: LatticeError) -> Self {
+        LcsfeError::Validation(e.to_string())
+    }
+}
+
+impl From<MovesError> for LcsfeError {
+    fn from(e: MovesError) -> Self {
+        LcsfeError::Validation(e.to_string())
+    }
+}
+
+impl From<CertificateError> for LcsfeError {
+    fn from(e: CertificateError) -> Self {
+        LcsfeError::Validation(e.to_string())
+    }
+}
+
+impl From<CheckpointError> for LcsfeError {
+    fn from(e: CheckpointError) -> Self {
+        LcsfeError::Validation(e.to_string())
+    }
+}
+
+impl From<QueryError> for LcsfeError {
+    fn from(e: QueryError) -> Self {
+        LcsfeError::Query(e.to_string())
+    }
+}
+
+impl From<ProblemError> for LcsfeError {
+    fn from(e: ProblemError) -> Self {
+        match e {
+            ProblemError::Parse(e) => e.into(),
+            ProblemError::Query(details) => LcsfeError::Query(details),
+            e => LcsfeError::Validation(e.to_string()),
+        }
+    }
+}
+
+impl From<Box<dyn std::error::Error>> for LcsfeError {
+    /// Recovers the typed error from the errors the local algorithm passes
+    /// through, e.g. those raised while saving a checkpoint.
+    fn from(e: Box<dyn std::error::Error>) -> Self {
+        let e = match e.downcast::<LcsfeError>() {
+            Ok(e) => return *e,
+            Err(e) => e,
+        };
+        let e = match e.downcast::<CheckpointError>() {
+            Ok(e) => return (*e).into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<QueryError>() {
+            Ok(e) => return (*e).into(),
+            Err(e) => e,
+        };
+        let e = match e.downcast::<io::Error>() {
+            Ok(e) => return (*e).into(),
+            Err(e) => e,
+        };
+        match e.downcast::<serde_json::Error>() {
+            Ok(e) => (*e).into(),
+            Err(e) => LcsfeError::Validation(e.to_string()),
+        }
+    }
+}
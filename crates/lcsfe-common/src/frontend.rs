@@ -0,0 +1,69 @@
+//! Frontends translate an input language, e.g. parity games, into a
+//! verification task for the local algorithm. The command line interface
+//! builds a command for each frontend in its registry, so that a new input
+//! language does not require changes to it.
+
+use std::path::Path;
+
+use crate::{LcsfeError, LintOutput, SpecOutput, Verdict};
+
+/// A positional argument of a frontend, or one of its solvers.
+#[derive(Debug, Clone, Copy)]
+pub struct Argument {
+    pub name: &'static str,
+    pub help: &'static str,
+}
+
+pub trait Frontend {
+    /// The name of the command, e.g. `pg`
+    fn name(&self) -> &'static str;
+    /// A description of the input language
+    fn about(&self) -> &'static str;
+    /// The files read by the frontend, in order
+    fn inputs(&self) -> &'static [Argument];
+    /// The argument which selects the position to verify, e.g. a node
+    fn query(&self) -> Argument;
+    /// The solvers other than the local algorithm, which are accepted by
+    /// `SpecOutput::verify_with`
+    fn solvers(&self) -> &'static [Argument] {
+        &[]
+    }
+    /// Reads the files, one for each of `inputs`, and builds the verification
+    /// task for the query, or for a default position, e.g. the initial state
+    /// of a transition system, if missing.
+    fn spec(
+        &self,
+        inputs: &[&Path],
+        query: Option<String>,
+    ) -> Result<Box<dyn SpecOutput>, LcsfeError>;
+    /// Reads the files, one for each of `inputs`, and summarises them without
+    /// solving.
+    fn lint(&self, inputs: &[&Path]) -> Result<LintOutput, LcsfeError>;
+    /// Describes the verdict in the terms of the input, as
+    /// `SpecOutput::describe`, also for the tasks read from the cache.
+    fn describe(&self, verdict: &Verdict) -> String {
+        verdict.to_string()
+    }
+}
+
+/// The frontends available to the command line interface, in order.
+#[derive(Default)]
+pub struct Registry {
+    frontends: Vec<Box<dyn Frontend>>,
+}
+
+impl Registry {
+    /// Adds a frontend, in place of the one with the same name if any.
+    pub fn register(&mut self, frontend: impl Frontend + 'static) {
+        self.frontends.retain(|f| f.name() != frontend.name());
+        self.frontends.push(Box::new(frontend));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Frontend> {
+        self.iter().find(|f| f.name() == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Frontend> {
+        self.frontends.iter().map(|f| f.as_ref())
+    }
+}
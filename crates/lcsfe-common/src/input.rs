@@ -0,0 +1,40 @@
+//! Inputs are read from files, or from stdin when the path is `-`, e.g. to
+//! pipe in the output of another tool. Stdin can be read only once per run.
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::LcsfeError;
+
+/// The path which stands for stdin.
+pub const STDIN: &str = "-";
+
+static STDIN_TAKEN: AtomicBool = AtomicBool::new(false);
+
+pub fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == STDIN
+}
+
+/// Opens the file, or stdin if the path is `-`.
+pub fn open_input(path: &Path) -> Result<Box<dyn BufRead>, LcsfeError> {
+    if is_stdin(path) {
+        if STDIN_TAKEN.swap(true, Ordering::SeqCst) {
+            return Err(LcsfeError::Query(
+                "Only one input can be read from stdin".to_string(),
+            ));
+        }
+        Ok(Box::new(std::io::stdin().lock()))
+    } else {
+        Ok(Box::new(BufReader::new(LcsfeError::open(path)?)))
+    }
+}
+
+/// Reads the whole file, or stdin if the path is `-`.
+pub fn read_input(path: &Path) -> Result<String, LcsfeError> {
+    let mut src = String::new();
+    open_input(path)?
+        .read_to_string(&mut src)
+        .map_err(|e| LcsfeError::io(path, e))?;
+    Ok(src)
+}
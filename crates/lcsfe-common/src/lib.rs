@@ -1,6 +1,40 @@
+mod cache;
+mod checkpoint;
 mod cli_io;
+mod error;
+mod frontend;
+mod input;
+mod verdict;
 
+pub use cli_io::json_batch_output;
+pub use cli_io::json_output;
+pub use cli_io::BatchOutput;
 pub use cli_io::InputFlags;
+pub use cli_io::LintOutput;
+pub use cli_io::LintValue;
 pub use cli_io::PreProcOutput;
 pub use cli_io::SpecOutput;
 pub use cli_io::VerificationOutput;
+
+pub use error::LcsfeError;
+
+pub use frontend::Argument;
+pub use frontend::Frontend;
+pub use frontend::Registry;
+
+pub use input::is_stdin;
+pub use input::open_input;
+pub use input::read_input;
+pub use input::STDIN;
+
+pub use verdict::Statistics;
+pub use verdict::Verdict;
+pub use verdict::VerdictQuery;
+
+pub use cache::cached_spec;
+pub use cache::Position;
+pub use cache::Positions;
+
+pub use checkpoint::hash_inputs;
+pub use checkpoint::local_check;
+pub use checkpoint::CheckpointFlags;
@@ -0,0 +1,67 @@
+use std::fmt::Display;
+
+use lcsfe_algorithm::algorithm::{Certificate, Player};
+
+/// The result of a verification task, i.e. the winner of the powerset game
+/// from the position asked about, along with what was solved to find it.
+#[derive(Debug, Clone)]
+pub struct Verdict {
+    pub winner: Player,
+    pub query: VerdictQuery,
+    /// A certificate for the winner, if one was requested
+    pub evidence: Option<Certificate>,
+    /// Missing when the task is not solved through a system of fixpoint
+    /// equations, e.g. by Zielonka's algorithm
+    pub statistics: Option<Statistics>,
+}
+
+/// The position a verification task asks about.
+#[derive(Debug, Clone)]
+pub struct VerdictQuery {
+    /// The basis elements, a single one unless the query is about a set
+    pub basis_elements: Vec<String>,
+    /// The index of the variable in the system which is solved
+    pub index: usize,
+    pub variable: String,
+    /// What the position stands for in the input, e.g. a node of a parity
+    /// game or a state of a transition system
+    pub original: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Statistics {
+    pub equations: usize,
+    pub basis_size: usize,
+}
+
+impl Verdict {
+    /// Whether the property holds, i.e. the existential player wins.
+    pub fn holds(&self) -> bool {
+        self.winner == Player::Eve
+    }
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The winner from {} is the {}", self.query, self.winner)
+    }
+}
+
+impl Display for VerdictQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.basis_elements.as_slice() {
+            [b] => write!(f, "({}, {})", b, self.variable),
+            bs => write!(f, "({{{}}}, {})", bs.join(", "), self.variable),
+        }
+    }
+}
+
+impl Display for Statistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} equations, {} basis elements",
+            self.equations, self.basis_size
+        )
+    }
+}
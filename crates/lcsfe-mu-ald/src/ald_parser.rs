@@ -1,8 +1,12 @@
-use rustc_hash::FxHashMap as HashMap;
-use std::fs::File;
+use lcsfe_algorithm::parse::{Diagnostic, ParserError};
+use lcsfe_common::LcsfeError;
 use std::io::BufRead;
-use std::io::BufReader;
-use std::io::Error;
+
+use crate::lts::{Lts, LtsBuilder};
+
+fn parse_error(what: &str, line_nr: usize, line: &str) -> LcsfeError {
+    ParserError::from_diagnostics(vec![Diagnostic::on_line(line_nr, line, what.to_string())]).into()
+}
 
 // aut_header        ::=  'des (' first_state ',' nr_of_transitions ',' nr_of_states ')'
 // first_state       ::=  number
@@ -12,59 +16,54 @@ use std::io::Error;
 // start_state ::=  number
 // label       ::=  '"' string '"'
 // end_state   ::=  number
-#[derive(Debug)]
-pub struct Lts {
-    pub first_state: u32,
-    pub labels: Vec<String>,
-    pub adj_list: HashMap<u32, Vec<(usize, u32)>>,
-}
+pub fn ald_parser(src: impl BufRead) -> Result<Lts, LcsfeError> {
+    let mut lines = src.lines();
+    let header = lines
+        .next()
+        .ok_or_else(|| parse_error("missing header", 1, ""))??;
+    let fields = header
+        .trim()
+        .strip_prefix("des (")
+        .and_then(|x| x.strip_suffix(')'))
+        .map(|x| {
+            x.split(',')
+                .map(|x| x.trim().parse::<u32>())
+                .collect::<Result<Vec<_>, _>>()
+        });
+    let (first_state, nr_of_transitions, nr_of_states) = match fields {
+        Some(Ok(fields)) if fields.len() == 3 => {
+            (fields[0], fields[1] as usize, fields[2] as usize)
+        }
+        _ => return Err(parse_error("invalid header", 1, &header)),
+    };
 
-pub fn ald_parser(src: &mut BufReader<File>) -> Result<Lts, Error> {
-    let header: String = src.lines().next().unwrap()?;
-    let header: Vec<u32> = header
-        .trim_start_matches("des (")
-        .trim_end_matches(')')
-        .split(',')
-        .map(|x| x.parse::<u32>().expect("Expected natural number"))
-        .collect();
-    let first_state = header[0];
-    let nr_of_states: usize = header[2].try_into().unwrap();
-    let mut labels: Vec<String> = vec![];
-    let adj_list = src.lines().try_fold(
-        HashMap::default(),
-        |mut acc: HashMap<u32, Vec<(usize, u32)>>, elem| match elem.map(|x| {
-            let x_trim = x.trim_start_matches('(').trim_end_matches(')');
-            let start = x_trim.splitn(2, ',').collect::<Vec<&str>>();
-            let label: Vec<&str> = start[1].trim_start_matches('"').splitn(2, '"').collect();
-            let end = label[1].trim_start_matches(',');
+    let mut builder = LtsBuilder::new(first_state);
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (start_node, label, end_node) =
+            parse_edge(&line).ok_or_else(|| parse_error("invalid transition", i + 2, &line))?;
+        builder.add_transition(start_node, label, end_node);
+    }
+    let mut lts = builder.build()?;
+    lts.nr_of_transitions = nr_of_transitions;
 
-            vec![start[0].to_string(), label[0].to_string(), end.to_string()]
-        }) {
-            Ok(edge) => {
-                let start_node: u32 = edge[0].parse().unwrap();
-                let label: String = edge[1].parse().unwrap();
-                let end_node: u32 = edge[2].parse().unwrap();
-                let position: usize = if let Some(i) = labels.iter().position(|x| x == &label) {
-                    i
-                } else {
-                    labels.push(label.to_owned());
-                    labels.len() - 1
-                };
-                if let Some(edges) = acc.get_mut(&start_node) {
-                    edges.push((position, end_node));
-                } else {
-                    acc.insert(start_node, vec![(position, end_node)]);
-                }
-                Ok(acc)
-            }
-            Err(e) => Err(e),
-        },
-    );
+    if lts.adj_list.len() != nr_of_states {
+        return Err(LcsfeError::Validation(format!(
+            "Invalid LTS: the header declares {} states, but {} states have outgoing transitions",
+            nr_of_states,
+            lts.adj_list.len()
+        )));
+    }
+    Ok(lts)
+}
 
-    assert!(adj_list.as_ref().unwrap().len() == nr_of_states);
-    Ok(Lts {
-        first_state,
-        labels,
-        adj_list: adj_list?,
-    })
+fn parse_edge(line: &str) -> Option<(u32, &str, u32)> {
+    let x_trim = line.trim().strip_prefix('(')?.strip_suffix(')')?;
+    let (start, rest) = x_trim.split_once(',')?;
+    let (label, end) = rest.trim_start().strip_prefix('"')?.rsplit_once('"')?;
+    let end = end.trim_start().strip_prefix(',')?;
+    Some((start.trim().parse().ok()?, label, end.trim().parse().ok()?))
 }
@@ -1,17 +1,29 @@
 mod ald_parser;
+pub mod lts;
 mod mu_calc_parser;
 
-use ald_parser::{ald_parser, Lts};
+use ald_parser::ald_parser;
 use chumsky::Parser;
-use mu_calc_parser::MuCalc;
 use lcsfe_algorithm::{
     algorithm::{LocalAlgorithm, Player},
-    ast::symbolic_moves_composed::SymbolicExistsMoves,
+    ast::{
+        fixpoint_system::{alternation_depth, FixEq},
+        symbolic_moves_composed::SymbolicExistsMoves,
+    },
     normalizer::normalize_system,
+    parse::ParserError,
+};
+use lcsfe_common::{
+    open_input, Argument, Frontend, InputFlags, LcsfeError, LintOutput, Position, Positions,
+    PreProcOutput, SpecOutput, Verdict, VerdictQuery, VerificationOutput,
+};
+use lts::Lts;
+use mu_calc_parser::MuCalc;
+use rustc_hash::FxHashMap as HashMap;
+use std::{
+    io::{BufRead, Read},
+    time::Instant,
 };
-use lcsfe_common::{InputFlags, PreProcOutput, SpecOutput, VerificationOutput};
-use std::collections::HashMap;
-use std::{io::Read, time::Instant};
 
 pub struct MuAld {
     lts: Lts,
@@ -20,81 +32,372 @@ pub struct MuAld {
 }
 
 impl MuAld {
+    /// Reads a transition system in ALD format and a formula, e.g. from files
+    /// or stdin.
     pub fn new(
-        lts_src: &mut std::io::BufReader<std::fs::File>,
-        formula_src: &mut std::io::BufReader<std::fs::File>,
+        lts_src: impl BufRead,
+        formula_src: impl Read,
         state: String,
-    ) -> Result<MuAld, Box<dyn std::error::Error>> {
-        let lts = ald_parser(lts_src)?;
-        let mut formula = String::new();
-        formula_src
-            .read_to_string(&mut formula)
-            .expect("cannot read string");
-        let formula = mu_calc_parser::mu_calc_parser(&lts.labels)
-            .parse(formula)
-            .unwrap();
-        Ok(MuAld {
+    ) -> Result<MuAld, LcsfeError> {
+        let (lts, formula) = parse(lts_src, formula_src)?;
+        Self::from_parsed(lts, formula, Some(state))
+    }
+
+    /// As `new`, with the transition system and the formula in strings.
+    pub fn from_source(lts: &str, formula: &str, state: String) -> Result<MuAld, LcsfeError> {
+        Self::new(lts.as_bytes(), formula.as_bytes(), state)
+    }
+
+    /// The verification task on a transition system built in code, see
+    /// `lts::LtsBuilder`. The query is about `state`, or the initial state if
+    /// missing.
+    pub fn from_lts(lts: Lts, formula: &str, state: Option<String>) -> Result<MuAld, LcsfeError> {
+        let formula = parse_formula(&lts, formula)?;
+        Self::from_parsed(lts, formula, state)
+    }
+
+    /// The query is about `state`, or the initial state if missing.
+    fn from_parsed(lts: Lts, formula: MuCalc, state: Option<String>) -> Result<MuAld, LcsfeError> {
+        let state = state.unwrap_or_else(|| lts.first_state.to_string());
+        let mu_ald = MuAld {
             lts,
             formula,
             state,
-        })
+        };
+        mu_ald.check_state(&mu_ald.state)?;
+        Ok(mu_ald)
     }
-}
 
-impl SpecOutput for MuAld {
-    fn pre_proc(&self, flags: &InputFlags) -> Result<PreProcOutput, Box<dyn std::error::Error>> {
-        let start = Instant::now();
+    /// Fails unless `state` names a state of the system, as it is displayed.
+    fn check_state(&self, state: &str) -> Result<(), LcsfeError> {
+        let found = state
+            .parse::<u32>()
+            .is_ok_and(|x| x.to_string() == state && self.lts.adj_list.contains_key(&x));
+        if found {
+            Ok(())
+        } else {
+            Err(LcsfeError::Query(format!(
+                "Cannot find state with name {}",
+                state
+            )))
+        }
+    }
+
+    /// Translates the formula and composes its moves over the states, without
+    /// naming them, e.g. to check many states of a system built in code.
+    pub fn state_system(&self, normalize: bool) -> Result<StateSystem, LcsfeError> {
         let (fix_system, moves) = mu_calc_parser::mucalc_to_fix_system(&self.formula, &self.lts)?;
-        let (fix_system, var_map) = if flags.normalize {
+        let (fix_system, var_map) = if normalize {
             normalize_system(fix_system)
         } else {
             (fix_system, HashMap::default())
         };
-        let moves_composed = SymbolicExistsMoves::compose(
+        let moves = SymbolicExistsMoves::compose(
             &fix_system,
             &moves,
-            &self
-                .lts
-                .adj_list
-                .iter()
-                .map(|x| x.0.to_string())
-                .collect::<Vec<_>>(),
-        );
+            &self.lts.adj_list.keys().copied().collect::<Vec<_>>(),
+        )?;
+        Ok(StateSystem {
+            fix_system,
+            var_map,
+            moves,
+        })
+    }
+
+    /// Parses and translates the inputs without solving them, and summarises
+    /// the transition system and the system of fixpoint equations.
+    pub fn lint(lts_src: impl BufRead, formula_src: impl Read) -> Result<LintOutput, LcsfeError> {
+        let (lts, formula) = parse(lts_src, formula_src)?;
+        Self::summarise(&lts, &formula)
+    }
+
+    /// As `lint`, on a transition system built in code.
+    pub fn lint_lts(lts: Lts, formula: &str) -> Result<LintOutput, LcsfeError> {
+        Self::summarise(&lts, &parse_formula(&lts, formula)?)
+    }
+
+    fn summarise(lts: &Lts, formula: &MuCalc) -> Result<LintOutput, LcsfeError> {
+        let (fix_system, _) = mu_calc_parser::mucalc_to_fix_system(formula, lts)?;
+
+        let transitions = lts.adj_list.values().map(Vec::len).sum::<usize>();
+        let mut unreachable = lts.unreachable_states();
+        unreachable.sort();
+
+        let mut lint = LintOutput::default();
+        lint.push("States", lts.adj_list.len());
+        lint.push("Transitions", transitions);
+        lint.push("Labels", lts.labels.len());
+        lint.push("Unreachable states", unreachable);
+        lint.push("Equations", fix_system.len());
+        lint.push("Alternation depth", alternation_depth(&fix_system));
+        if transitions != lts.nr_of_transitions {
+            lint.warnings.push(format!(
+                "the header declares {} transitions, but there are {}",
+                lts.nr_of_transitions, transitions
+            ));
+        }
+        Ok(lint)
+    }
+}
+
+fn parse(lts_src: impl BufRead, mut formula_src: impl Read) -> Result<(Lts, MuCalc), LcsfeError> {
+    let lts = ald_parser(lts_src)?;
+    let mut formula = String::new();
+    formula_src.read_to_string(&mut formula)?;
+    let formula = parse_formula(&lts, &formula)?;
+    Ok((lts, formula))
+}
+
+/// Parses a formula over the labels of `lts`.
+fn parse_formula(lts: &Lts, src: &str) -> Result<MuCalc, LcsfeError> {
+    Ok(mu_calc_parser::mu_calc_parser(&lts.labels)
+        .parse(src)
+        .map_err(|errs| ParserError::from_simple(src, errs))?)
+}
+
+/// The system of fixpoint equations of a formula and its composed moves,
+/// whose basis is the states of the transition system, which are queried by
+/// their identifier.
+pub struct StateSystem {
+    pub fix_system: Vec<FixEq>,
+    /// The map from the original variables to those of the normalized system,
+    /// empty if the system is not normalized
+    pub var_map: HashMap<String, String>,
+    pub moves: SymbolicExistsMoves<u32>,
+}
+
+impl StateSystem {
+    /// Whether the formula is satisfied from `state`: it is if the existential
+    /// player wins.
+    pub fn check(&self, state: u32) -> Result<Player, LcsfeError> {
+        if self.moves.basis_index(&state).is_none() {
+            return Err(LcsfeError::Query(format!(
+                "Cannot find state with name {}",
+                state
+            )));
+        }
+        let local_algorithm = LocalAlgorithm {
+            fix_system: &self.fix_system,
+            symbolic_moves: &self.moves,
+            up_to: None,
+        };
+        Ok(local_algorithm.local_check(state, self.fix_system.len() - 1)?)
+    }
+}
+
+impl SpecOutput for MuAld {
+    fn pre_proc(&self, flags: &InputFlags) -> Result<PreProcOutput, LcsfeError> {
+        let start = Instant::now();
+        let system = self.state_system(flags.normalize)?;
+        // The states are named only once composed, for the output
+        let moves = system.moves.rename_basis(u32::to_string);
         let preproc_time = start.elapsed();
 
         Ok(PreProcOutput {
-            moves: moves_composed,
-            fix_system,
-            var_map,
-            var: self.state.to_owned(),
+            moves,
+            fix_system: system.fix_system,
+            var_map: system.var_map,
+            var: self.query_var(),
             preproc_time,
+            cached: false,
         })
     }
 
+    fn query_var(&self) -> String {
+        self.state.to_owned()
+    }
+
     fn verify(
         &self,
-        _: &InputFlags,
+        flags: &InputFlags,
+        pre_proc: &PreProcOutput,
+    ) -> Result<VerificationOutput, LcsfeError> {
+        self.verify_at(flags, pre_proc, &self.state)
+    }
+
+    fn positions(&self) -> Vec<String> {
+        let mut states = self.lts.adj_list.keys().collect::<Vec<_>>();
+        states.sort();
+        states.into_iter().map(|x| x.to_string()).collect()
+    }
+
+    fn verify_at(
+        &self,
+        flags: &InputFlags,
         pre_proc: &PreProcOutput,
-    ) -> Result<lcsfe_common::VerificationOutput, Box<dyn std::error::Error>> {
+        state: &str,
+    ) -> Result<VerificationOutput, LcsfeError> {
+        // The composed moves index the states by name, which is cheaper than
+        // naming every state of the system, e.g. for each state with `--all`
+        if pre_proc.moves.basis_index(state).is_none() {
+            return Err(LcsfeError::Query(format!(
+                "Cannot find state with name {}",
+                state
+            )));
+        }
         let local_algorithm = LocalAlgorithm {
             fix_system: &pre_proc.fix_system,
             symbolic_moves: &pre_proc.moves,
+            up_to: None,
         };
 
+        let index = local_algorithm.fix_system.len() - 1;
         let start = Instant::now();
-        let result = local_algorithm
-            .local_check(self.state.to_owned(), local_algorithm.fix_system.len() - 1);
+        let winner = lcsfe_common::local_check(&local_algorithm, state.to_owned(), index, flags)?;
         let algorithm_time = start.elapsed();
 
-        let result = match result {
-            Player::Eve => "is satisfied",
-            Player::Adam => "is not satisfied",
+        let verdict = Verdict {
+            winner,
+            query: VerdictQuery {
+                basis_elements: vec![state.to_owned()],
+                index,
+                variable: pre_proc.fix_system[index].var.to_owned(),
+                original: state.to_owned(),
+            },
+            evidence: None,
+            statistics: Some(pre_proc.statistics()),
         };
-
-        let result = format!("The property {} from state {}", result, self.state);
         Ok(VerificationOutput {
             algorithm_time,
-            result,
+            result: self.describe(&verdict),
+            verdict,
         })
     }
+
+    fn set_formula(&mut self, src: &str) -> Result<(), LcsfeError> {
+        let formula = parse_formula(&self.lts, src)?;
+        // The formula is kept only if it can be translated
+        mu_calc_parser::mucalc_to_fix_system(&formula, &self.lts)?;
+        self.formula = formula;
+        Ok(())
+    }
+
+    fn locate(
+        &self,
+        _flags: &InputFlags,
+        pre_proc: &PreProcOutput,
+    ) -> Result<Positions, LcsfeError> {
+        let index = pre_proc.fix_system.len() - 1;
+        Ok(Positions {
+            default: self.lts.first_state.to_string(),
+            positions: self
+                .positions()
+                .into_iter()
+                .map(|state| Position {
+                    basis_element: state.to_owned(),
+                    index,
+                    var: state.to_owned(),
+                    name: state,
+                })
+                .collect(),
+        })
+    }
+
+    fn describe(&self, verdict: &Verdict) -> String {
+        MuAldFrontend.describe(verdict)
+    }
+}
+
+/// The frontend for mu-calculus formulae on transition systems in Aldebaran
+/// format.
+pub struct MuAldFrontend;
+
+impl Frontend for MuAldFrontend {
+    fn name(&self) -> &'static str {
+        "mu-ald"
+    }
+
+    fn about(&self) -> &'static str {
+        "A model checker for mu-calculus formulae on transition systems in Aldebaran format"
+    }
+
+    fn inputs(&self) -> &'static [Argument] {
+        &[
+            Argument {
+                name: "lts_ald",
+                help: "Path to a file containing an Aldebaran specification",
+            },
+            Argument {
+                name: "mu_calc",
+                help: "Path to a file containing a mu-calculus formula",
+            },
+        ]
+    }
+
+    fn query(&self) -> Argument {
+        Argument {
+            name: "state",
+            help: "The state of the Aldebaran specification from which the verification starts",
+        }
+    }
+
+    fn spec(
+        &self,
+        inputs: &[&std::path::Path],
+        query: Option<String>,
+    ) -> Result<Box<dyn SpecOutput>, LcsfeError> {
+        let (lts, formula) = parse(open_input(inputs[0])?, open_input(inputs[1])?)?;
+        Ok(Box::new(MuAld::from_parsed(lts, formula, query)?))
+    }
+
+    fn lint(&self, inputs: &[&std::path::Path]) -> Result<LintOutput, LcsfeError> {
+        MuAld::lint(open_input(inputs[0])?, open_input(inputs[1])?)
+    }
+
+    fn describe(&self, verdict: &Verdict) -> String {
+        let holds = match verdict.winner {
+            Player::Eve => "is satisfied",
+            Player::Adam => "is not satisfied",
+        };
+        format!(
+            "The property {} from state {}",
+            holds, verdict.query.original
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MuAld;
+    use lcsfe_common::{InputFlags, SpecOutput};
+
+    /// Checking the states by their identifier gives the verdicts of the
+    /// queries by name, with and without normalization.
+    #[test]
+    fn state_system_agrees_with_names() {
+        let src = |f: &str| {
+            std::fs::read_to_string(format!(
+                "{}/../../tests/example_mucalc/{}",
+                env!("CARGO_MANIFEST_DIR"),
+                f
+            ))
+            .unwrap()
+        };
+        let spec = MuAld::from_source(
+            &src("bridge-referee.aut"),
+            &src("deadlock-liveness"),
+            "0".into(),
+        )
+        .unwrap();
+        for normalize in [false, true] {
+            let flags = InputFlags {
+                normalize,
+                checkpoint: None,
+            };
+            let pre_proc = spec.pre_proc(&flags).unwrap();
+            let system = spec.state_system(normalize).unwrap();
+            for state in spec.positions() {
+                assert_eq!(
+                    system.check(state.parse().unwrap()).unwrap(),
+                    spec.verify_at(&flags, &pre_proc, &state)
+                        .unwrap()
+                        .verdict
+                        .winner,
+                    "state {}",
+                    state
+                );
+            }
+            assert!(system.check(u32::MAX).is_err());
+        }
+    }
 }
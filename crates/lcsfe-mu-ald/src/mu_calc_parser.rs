@@ -1,4 +1,4 @@
-use std::{fmt::Display, io::Error};
+use std::fmt::Display;
 
 use rustc_hash::FxHashMap as HashMap;
 use rustc_hash::FxHashSet as HashSet;
@@ -9,7 +9,8 @@ use lcsfe_algorithm::ast::{
     symbolic_moves::{LogicFormula, SymbolicExistsMoves},
 };
 
-use crate::ald_parser::Lts;
+use crate::lts::Lts;
+use lcsfe_common::LcsfeError;
 
 #[derive(Debug)]
 pub enum Act {
@@ -43,8 +44,8 @@ pub enum MuCalc {
 fn instantiate_diamond(
     lts: &Lts,
     act: &Act,
-    formulas: &mut [LogicFormula],
-    basis_map: &HashMap<String, usize>,
+    formulas: &mut [LogicFormula<u32>],
+    basis_map: &HashMap<u32, usize>,
     fun_map: &HashMap<String, usize>,
 ) {
     lts.adj_list.iter().for_each(|(basis_elem, edges)| {
@@ -59,12 +60,12 @@ fn instantiate_diamond(
             .peekable();
         if nodes.peek().is_none() {
             formulas[fun_map.get(&format!("diamond_{}", act)).unwrap() * basis_map.len()
-                + basis_map.get(&basis_elem.to_string()).unwrap()] = LogicFormula::False;
+                + basis_map[basis_elem]] = LogicFormula::False;
         } else {
             formulas[fun_map.get(&format!("diamond_{}", act)).unwrap() * basis_map.len()
-                + basis_map.get(&basis_elem.to_string()).unwrap()] = LogicFormula::Disj(
+                + basis_map[basis_elem]] = LogicFormula::Disj(
                 nodes
-                    .map(|n| LogicFormula::BasisElem(n.to_string(), 0))
+                    .map(|n| LogicFormula::BasisElem(*n, 0))
                     .collect::<Vec<_>>(),
             );
         }
@@ -74,8 +75,8 @@ fn instantiate_diamond(
 fn instantiate_box(
     lts: &Lts,
     act: &Act,
-    formulas: &mut [LogicFormula],
-    basis_map: &HashMap<String, usize>,
+    formulas: &mut [LogicFormula<u32>],
+    basis_map: &HashMap<u32, usize>,
     fun_map: &HashMap<String, usize>,
 ) {
     lts.adj_list.iter().for_each(|(basis_elem, edges)| {
@@ -90,22 +91,30 @@ fn instantiate_box(
             .peekable();
         if nodes.peek().is_none() {
             formulas[fun_map.get(&format!("box_{}", act)).unwrap() * basis_map.len()
-                + basis_map.get(&basis_elem.to_string()).unwrap()] = LogicFormula::True;
+                + basis_map[basis_elem]] = LogicFormula::True;
         } else {
             formulas[fun_map.get(&format!("box_{}", act)).unwrap() * basis_map.len()
-                + basis_map.get(&basis_elem.to_string()).unwrap()] = LogicFormula::Conj(
+                + basis_map[basis_elem]] = LogicFormula::Conj(
                 nodes
-                    .map(|n| LogicFormula::BasisElem(n.to_string(), 0))
+                    .map(|n| LogicFormula::BasisElem(*n, 0))
                     .collect::<Vec<_>>(),
             );
         }
     })
 }
 
+/// Translates the formula into a system of fixpoint equations, whose basis is
+/// the states of `lts`, along with its symbolic exists-moves.
 pub fn mucalc_to_fix_system(
     formula: &MuCalc,
     lts: &Lts,
-) -> Result<(Vec<FixEq>, SymbolicExistsMoves), Error> {
+) -> Result<(Vec<FixEq>, SymbolicExistsMoves<u32>), LcsfeError> {
+    if let Some(x) = free_variable(formula, &mut vec![]) {
+        return Err(LcsfeError::Validation(format!(
+            "Invalid formula: the variable {} is not bound by a fixpoint",
+            x
+        )));
+    }
     match &formula {
         MuCalc::Eta(_, _, _) => {
             let (var_counter, foos) = preproc_formula(formula);
@@ -113,8 +122,8 @@ pub fn mucalc_to_fix_system(
                 .adj_list
                 .iter()
                 .enumerate()
-                .map(|(i, x)| (x.0.to_string(), i))
-                .collect::<HashMap<String, usize>>();
+                .map(|(i, x)| (*x.0, i))
+                .collect::<HashMap<u32, usize>>();
 
             let fun_map = foos
                 .into_iter()
@@ -140,13 +149,31 @@ pub fn mucalc_to_fix_system(
                 },
             ))
         }
-        _ => Err(Error::new(
-            std::io::ErrorKind::Other,
-            "The input formula is not a fixpoint formula",
+        _ => Err(LcsfeError::Validation(
+            "Invalid formula: the input formula is not a fixpoint formula".to_string(),
         )),
     }
 }
 
+/// Returns a variable which occurs outside of the scope of the fixpoint
+/// binding it, if any.
+fn free_variable<'a>(formula: &'a MuCalc, bound: &mut Vec<&'a String>) -> Option<&'a String> {
+    match formula {
+        MuCalc::True | MuCalc::False => None,
+        MuCalc::Var(x) => (!bound.contains(&x)).then_some(x),
+        MuCalc::Eta(x, _, f) => {
+            bound.push(x);
+            let free = free_variable(f, bound);
+            bound.pop();
+            free
+        }
+        MuCalc::Diamond(_, f) | MuCalc::Box(_, f) => free_variable(f, bound),
+        MuCalc::And(l, r) | MuCalc::Or(l, r) => {
+            free_variable(l, bound).or_else(|| free_variable(r, bound))
+        }
+    }
+}
+
 /// in one visit I want to know:
 ///
 ///  - how many new variables I have to instantiate
@@ -193,9 +220,9 @@ fn preproc_formula(formula: &MuCalc) -> (u32, HashSet<String>) {
 fn get_fix_system(
     formula: &MuCalc,
     lts: &Lts,
-    basis_map: &HashMap<String, usize>,
+    basis_map: &HashMap<u32, usize>,
     fun_map: &HashMap<String, usize>,
-    formulas: &mut Vec<LogicFormula>,
+    formulas: &mut Vec<LogicFormula<u32>>,
     mut var_counter: u32,
     var_map: &mut HashMap<String, String>,
 ) -> (ExpFixEq, Vec<FixEq>) {
@@ -210,6 +237,7 @@ fn get_fix_system(
                 var: x_i.clone(),
                 fix_ty: fix_ty.clone(),
                 exp,
+                basis: None,
             });
             (ExpFixEq::Id(x_i), system)
         }
@@ -257,16 +285,16 @@ fn get_fix_system(
 
         MuCalc::True => {
             lts.adj_list.iter().for_each(|x| {
-                formulas[fun_map.get("tt").unwrap() * basis_map.len()
-                    + basis_map.get(&x.0.to_string()).unwrap()] = LogicFormula::True;
+                formulas[fun_map.get("tt").unwrap() * basis_map.len() + basis_map[x.0]] =
+                    LogicFormula::True;
             });
 
             (ExpFixEq::Operator("tt".to_string(), vec![]), vec![])
         }
         MuCalc::False => {
             lts.adj_list.iter().for_each(|x| {
-                formulas[fun_map.get("ff").unwrap() * basis_map.len()
-                    + basis_map.get(&x.0.to_string()).unwrap()] = LogicFormula::False;
+                formulas[fun_map.get("ff").unwrap() * basis_map.len() + basis_map[x.0]] =
+                    LogicFormula::False;
             });
             (ExpFixEq::Operator("ff".to_string(), vec![]), vec![])
         }
@@ -1,36 +1,163 @@
 mod parser;
-mod pg;
+pub mod pg;
 mod pg_to_system;
+mod zielonka;
 
-use pg::PG;
 use lcsfe_algorithm::{
     algorithm::{LocalAlgorithm, Player},
+    ast::fixpoint_system::alternation_depth,
     ast::symbolic_moves::SymbolicExistsMoves as UncomposedMoves,
     ast::symbolic_moves_composed::SymbolicExistsMoves,
     normalizer::normalize_system,
 };
-use lcsfe_common::{InputFlags, PreProcOutput, SpecOutput, VerificationOutput};
+use lcsfe_common::{
+    open_input, Argument, Frontend, InputFlags, LcsfeError, LintOutput, Position, Positions,
+    PreProcOutput, SpecOutput, Verdict, VerdictQuery, VerificationOutput,
+};
+use pg::PG;
 
 use rustc_hash::FxHashMap as HashMap;
+use std::io::BufRead;
 
 pub struct ParityGameSpec {
     pg: PG,
     node: String,
     position: usize,
+    /// The first node of the game, which is queried by default
+    first: String,
 }
 
 impl ParityGameSpec {
-    pub fn new(src: &mut std::io::BufReader<std::fs::File>, node: String) -> ParityGameSpec {
-        let mut pg = parser::parse_pg(src).unwrap();
-        pg.0.sort_by(|a, b| a.0.parity.partial_cmp(&b.0.parity).unwrap());
+    /// Reads a parity game in PGSolver format, e.g. from a file or stdin.
+    pub fn new(src: impl BufRead, node: String) -> Result<ParityGameSpec, LcsfeError> {
+        Self::from_game(parser::parse_pg(src)?, Some(node))
+    }
+
+    /// As `new`, with the parity game in a string.
+    pub fn from_source(src: &str, node: String) -> Result<ParityGameSpec, LcsfeError> {
+        Self::new(src.as_bytes(), node)
+    }
+
+    /// The verification task on a parity game built in code, see
+    /// `pg::ParityGameBuilder`. The query is about `node`, or the first node of
+    /// the game if missing.
+    pub fn from_game(mut pg: PG, node: Option<String>) -> Result<ParityGameSpec, LcsfeError> {
+        pg.validate()?;
+        let first =
+            pg.0.first()
+                .ok_or_else(|| LcsfeError::Query("The parity game has no nodes".to_string()))?
+                .0
+                .name
+                .to_owned();
+        let node = node.unwrap_or_else(|| first.to_owned());
+        pg.0.sort_by_key(|a| a.0.parity);
+
+        let mut spec = ParityGameSpec {
+            pg,
+            node,
+            position: 0,
+            first,
+        };
+        spec.position = spec.position(&spec.node)?;
+        Ok(spec)
+    }
+
+    /// The index of the equation of `node`, before normalization.
+    fn position(&self, node: &str) -> Result<usize, LcsfeError> {
+        self.pg
+            .0
+            .iter()
+            .position(|x| x.0.name == node)
+            .ok_or_else(|| LcsfeError::Query(format!("Cannot find node with name {}", node)))
+    }
+
+    /// Parses and translates the parity game without solving it, and
+    /// summarises the game and the system of fixpoint equations.
+    pub fn lint(src: impl BufRead) -> Result<LintOutput, LcsfeError> {
+        Self::lint_game(parser::parse_pg(src)?)
+    }
+
+    /// As `lint`, on a parity game built in code.
+    pub fn lint_game(mut pg: PG) -> Result<LintOutput, LcsfeError> {
+        pg.0.sort_by_key(|a| a.0.parity);
+        let fix_system = pg_to_system::pg_to_system(&pg, pg::Player::Eve);
+
+        let mut lint = LintOutput::default();
+        lint.push("Nodes", pg.0.len());
+        lint.push(
+            "Edges",
+            pg.0.iter().map(|(_, adj)| adj.len()).sum::<usize>(),
+        );
+        lint.push(
+            "Greatest priority",
+            pg.0.iter().map(|(n, _)| n.parity).max().unwrap_or(0),
+        );
+        lint.push("Equations", fix_system.len());
+        lint.push("Alternation depth", alternation_depth(&fix_system));
+
+        Ok(lint)
+    }
 
-        let position =
-            pg.0.iter()
-                .enumerate()
-                .find_map(|(i, x)| if x.0.name == node { Some(i) } else { None })
-                .unwrap_or_else(|| panic!("Cannot find node with name {}", node));
+    /// Solves the parity game with Zielonka's recursive algorithm, without
+    /// going through the translation to a system of fixpoint equations. It
+    /// can be used as a reference for the result of the local algorithm.
+    pub fn zielonka(&self) -> VerificationOutput {
+        self.zielonka_at(self.position)
+    }
+
+    fn zielonka_at(&self, position: usize) -> VerificationOutput {
+        let start = std::time::Instant::now();
+        let winners = zielonka::zielonka(&self.pg);
+        self.zielonka_output(position, &winners, start.elapsed())
+    }
+
+    /// The verdict from the node at `position`, given the winner of each
+    /// node as computed by `zielonka::zielonka` in `algo_duration`.
+    fn zielonka_output(
+        &self,
+        position: usize,
+        winners: &[pg::Player],
+        algo_duration: std::time::Duration,
+    ) -> VerificationOutput {
+        let winner = match winners[position] {
+            pg::Player::Adam => Player::Adam,
+            pg::Player::Eve => Player::Eve,
+        };
+
+        let verdict = Verdict {
+            winner,
+            query: self.query(
+                position,
+                position,
+                pg_to_system::var_name(self.pg.0[position].0.id),
+            ),
+            evidence: None,
+            statistics: None,
+        };
+        VerificationOutput {
+            algorithm_time: algo_duration,
+            result: self.describe(&verdict),
+            verdict,
+        }
+    }
 
-        ParityGameSpec { pg, node, position }
+    /// The query about the node at `position`, whose variable is the one at
+    /// `index` in the solved system.
+    fn query(&self, position: usize, index: usize, variable: String) -> VerdictQuery {
+        VerdictQuery {
+            basis_elements: vec!["true".to_string()],
+            index,
+            variable,
+            original: self.pg.0[position].0.name.to_owned(),
+        }
+    }
+}
+
+/// The number of the player in the PGSolver format.
+fn player_nr(p: &Player) -> usize {
+    match p {
+        Player::Eve => 0,
+        Player::Adam => 1,
     }
 }
 
@@ -39,55 +166,145 @@ impl SpecOutput for ParityGameSpec {
         &self,
         flags: &InputFlags,
         pre_proc: &PreProcOutput,
-    ) -> Result<VerificationOutput, Box<dyn std::error::Error>> {
+    ) -> Result<VerificationOutput, LcsfeError> {
+        self.verify_at(flags, pre_proc, &self.node)
+    }
+
+    fn positions(&self) -> Vec<String> {
+        let mut nodes = self.pg.0.iter().map(|(n, _)| n).collect::<Vec<_>>();
+        nodes.sort_by_key(|n| n.id);
+        nodes.into_iter().map(|n| n.name.to_owned()).collect()
+    }
+
+    fn verify_at(
+        &self,
+        flags: &InputFlags,
+        pre_proc: &PreProcOutput,
+        node: &str,
+    ) -> Result<VerificationOutput, LcsfeError> {
+        let position = self.position(node)?;
+        let var = pg_to_system::var_name(self.pg.0[position].0.id);
         let index = if flags.normalize {
             pre_proc
                 .fix_system
                 .iter()
-                .enumerate()
-                .find_map(|(i, fix_eq)| {
-                    if pre_proc.var_map.get(&pre_proc.var).unwrap() == &fix_eq.var {
-                        Some(i)
-                    } else {
-                        None
-                    }
-                })
-                .unwrap()
+                .position(|fix_eq| pre_proc.var_map.get(&var) == Some(&fix_eq.var))
+                .ok_or_else(|| {
+                    LcsfeError::Query(format!("Cannot find the variable {} of node {}", var, node))
+                })?
         } else {
-            self.position
+            position
         };
 
         let algo = LocalAlgorithm {
             fix_system: &pre_proc.fix_system,
             symbolic_moves: &pre_proc.moves,
+            up_to: None,
         };
 
         let start = std::time::Instant::now();
-        let winner = algo.local_check("true".to_string(), index);
+        let winner = lcsfe_common::local_check(&algo, "true".to_string(), index, flags)?;
         let algo_duration = start.elapsed();
 
-        let winner = match winner {
-            Player::Adam => 1,
-            Player::Eve => 0,
+        let verdict = Verdict {
+            winner,
+            query: self.query(position, index, pre_proc.fix_system[index].var.to_owned()),
+            evidence: None,
+            statistics: Some(pre_proc.statistics()),
         };
-
-        Ok(lcsfe_common::VerificationOutput {
+        Ok(VerificationOutput {
             algorithm_time: algo_duration,
-            result: format!("Player {} wins from vertex {}", winner, self.node),
+            result: self.describe(&verdict),
+            verdict,
         })
     }
 
-    fn pre_proc(&self, flags: &InputFlags) -> Result<PreProcOutput, Box<dyn std::error::Error>> {
+    fn locate(
+        &self,
+        flags: &InputFlags,
+        pre_proc: &PreProcOutput,
+    ) -> Result<Positions, LcsfeError> {
+        let indices = pre_proc
+            .fix_system
+            .iter()
+            .enumerate()
+            .map(|(i, fix_eq)| (fix_eq.var.as_str(), i))
+            .collect::<HashMap<_, _>>();
+        let mut positions = Vec::with_capacity(self.pg.0.len());
+        for (position, (node, _)) in self.pg.0.iter().enumerate() {
+            let var = pg_to_system::var_name(node.id);
+            let index = if flags.normalize {
+                pre_proc
+                    .var_map
+                    .get(&var)
+                    .and_then(|x| indices.get(x.as_str()))
+                    .copied()
+                    .ok_or_else(|| {
+                        LcsfeError::Query(format!(
+                            "Cannot find the variable {} of node {}",
+                            var, node.name
+                        ))
+                    })?
+            } else {
+                position
+            };
+            positions.push((
+                node.id,
+                Position {
+                    name: node.name.to_owned(),
+                    basis_element: "true".to_string(),
+                    index,
+                    var,
+                },
+            ));
+        }
+        // In the order of `positions`
+        positions.sort_by_key(|(id, _)| *id);
+        Ok(Positions {
+            default: self.first.to_owned(),
+            positions: positions.into_iter().map(|(_, x)| x).collect(),
+        })
+    }
+
+    fn describe(&self, verdict: &Verdict) -> String {
+        ParityGameFrontend.describe(verdict)
+    }
+
+    fn verify_with(&self, solver: &str, node: &str) -> Result<VerificationOutput, LcsfeError> {
+        match solver {
+            "zielonka" => Ok(self.zielonka_at(self.position(node)?)),
+            _ => Err(LcsfeError::Query(format!("Unknown solver {}", solver))),
+        }
+    }
+
+    fn verify_many_with(
+        &self,
+        solver: &str,
+        nodes: &[String],
+    ) -> Result<Vec<VerificationOutput>, LcsfeError> {
+        if solver != "zielonka" {
+            return Err(LcsfeError::Query(format!("Unknown solver {}", solver)));
+        }
+        let positions = nodes
+            .iter()
+            .map(|node| self.position(node))
+            .collect::<Result<Vec<_>, _>>()?;
+        let start = std::time::Instant::now();
+        let winners = zielonka::zielonka(&self.pg);
+        let mut algo_duration = start.elapsed();
+        Ok(positions
+            .into_iter()
+            .map(|position| {
+                self.zielonka_output(position, &winners, std::mem::take(&mut algo_duration))
+            })
+            .collect())
+    }
+
+    fn pre_proc(&self, flags: &InputFlags) -> Result<PreProcOutput, LcsfeError> {
         let basis = vec!["true".to_string()];
 
         let start = std::time::Instant::now();
         let fix_system = pg_to_system::pg_to_system(&self.pg, pg::Player::Eve);
-        let var_name = fix_system
-            .iter()
-            .enumerate()
-            .find(|(i, _)| i == &self.position)
-            .map(|(_, x)| x.var.to_owned())
-            .unwrap();
         let fix_system = if flags.normalize {
             normalize_system(fix_system)
         } else {
@@ -103,15 +320,78 @@ impl SpecOutput for ParityGameSpec {
                 formulas: Vec::default(),
             },
             &basis,
-        );
+        )?;
         let preproc_duration = start.elapsed();
 
         Ok(PreProcOutput {
             moves: composed_system,
             fix_system: fix_system.0,
             var_map: fix_system.1,
-            var: var_name,
+            var: self.query_var(),
             preproc_time: preproc_duration,
+            cached: false,
         })
     }
+
+    fn query_var(&self) -> String {
+        pg_to_system::var_name(self.pg.0[self.position].0.id)
+    }
+}
+
+/// The frontend for parity games in PGSolver format.
+pub struct ParityGameFrontend;
+
+impl Frontend for ParityGameFrontend {
+    fn name(&self) -> &'static str {
+        "pg"
+    }
+
+    fn about(&self) -> &'static str {
+        "A solver for parity games"
+    }
+
+    fn inputs(&self) -> &'static [Argument] {
+        &[Argument {
+            name: "game_path",
+            help: "A path to a file containing a parity game, in PGSolver format",
+        }]
+    }
+
+    fn query(&self) -> Argument {
+        Argument {
+            name: "node",
+            help: "The node from which is verified whether if the selected player has a \
+                   winning strategy",
+        }
+    }
+
+    fn solvers(&self) -> &'static [Argument] {
+        &[Argument {
+            name: "zielonka",
+            help: "Zielonka's recursive algorithm, directly on the parity game",
+        }]
+    }
+
+    fn spec(
+        &self,
+        inputs: &[&std::path::Path],
+        query: Option<String>,
+    ) -> Result<Box<dyn SpecOutput>, LcsfeError> {
+        Ok(Box::new(ParityGameSpec::from_game(
+            parser::parse_pg(open_input(inputs[0])?)?,
+            query,
+        )?))
+    }
+
+    fn lint(&self, inputs: &[&std::path::Path]) -> Result<LintOutput, LcsfeError> {
+        ParityGameSpec::lint(open_input(inputs[0])?)
+    }
+
+    fn describe(&self, verdict: &Verdict) -> String {
+        format!(
+            "Player {} wins from vertex {}",
+            player_nr(&verdict.winner),
+            verdict.query.original
+        )
+    }
 }
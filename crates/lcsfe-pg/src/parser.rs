@@ -1,10 +1,8 @@
 use crate::pg::{Node, Player, PG};
 
-use std::io::Error;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
+use lcsfe_algorithm::parse::{Diagnostic, ParserError};
+use lcsfe_common::LcsfeError;
+use std::io::BufRead;
 
 /// Parses a string which respects the following EBNF grammar:
 ///
@@ -16,45 +14,78 @@ use std::{
 /// 〈successors〉 ::= 〈identifier 〉 (, 〈identifier 〉)∗
 /// 〈name〉 ::= " ( any ASCII string not containing ‘"’) "
 ///
-/// There is no error handling, a wrong input may parse and lead to weird
-/// behaviour.
+/// Fails if a node specification is malformed, or if the game is not valid,
+/// see `PG::validate`.
 ///
-pub fn parse_pg(src: &mut BufReader<File>) -> Result<PG, Error> {
-    src.lines().skip(1).fold(Ok(PG(vec![])), |acc, elem| {
-        match elem {
-            Ok(str) if str.trim().is_empty() => acc,
-            Ok(str) => {
-                let str: Vec<&str> = str.split_whitespace().collect::<Vec<_>>();
+pub fn parse_pg(src: impl BufRead) -> Result<PG, LcsfeError> {
+    let pg = src
+        .lines()
+        .enumerate()
+        .skip(1)
+        .try_fold(PG(vec![]), |mut pg, (i, elem)| {
+            let line = elem?;
+            if line.trim().is_empty() {
+                return Ok(pg);
+            }
+            pg.0.push(parse_node(i + 1, &line)?);
+            Ok::<_, LcsfeError>(pg)
+        })?;
 
-                let node = Node {
-                    owner: if str[2].parse::<u32>().unwrap() == 0 {
-                        Player::Eve
-                    } else {
-                        Player::Adam
-                    }, //
-                    name: if str.len() == 4 {
-                        str[0].parse::<String>().unwrap()
-                    } else {
-                        str[4]
-                            .trim_end_matches("\";")
-                            .trim_start_matches('"')
-                            .trim()
-                            .to_owned()
-                    },
-                    id: str[0].parse::<u32>().unwrap(),
-                    parity: str[1].parse::<u32>().unwrap(),
-                };
-                let adj_list = str[3]
-                    .split(',')
-                    .map(|x| x.parse::<usize>().unwrap())
-                    .collect::<Vec<_>>();
+    pg.validate()?;
+    Ok(pg)
+}
 
-                acc.map(|mut pg| {
-                    pg.0.push((node, adj_list));
-                    pg
-                })
-            }
-            Err(e) => Err(e),
-        }
-    })
+/// Parses the node specification at line `line_nr`.
+fn parse_node(line_nr: usize, line: &str) -> Result<(Node, Vec<usize>), LcsfeError> {
+    let error = |what: &str| {
+        LcsfeError::from(ParserError::from_diagnostics(vec![Diagnostic::on_line(
+            line_nr,
+            line,
+            format!("{} in the node specification", what),
+        )]))
+    };
+    let number = |field: Option<&str>, what: &str| {
+        field
+            .map(|x| x.trim_end_matches(';'))
+            .ok_or_else(|| error(&format!("missing {}", what)))?
+            .parse::<u32>()
+            .map_err(|_| error(&format!("invalid {}", what)))
+    };
+
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let id = number(fields.first().copied(), "identifier")?;
+    let parity = number(fields.get(1).copied(), "priority")?;
+    let owner = match number(fields.get(2).copied(), "owner")? {
+        0 => Player::Eve,
+        1 => Player::Adam,
+        _ => return Err(error("invalid owner")),
+    };
+    let adj_list = fields
+        .get(3)
+        .ok_or_else(|| error("missing successors"))?
+        .trim_end_matches(';')
+        .split(',')
+        .map(|x| x.parse::<usize>().map_err(|_| error("invalid successors")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let name = if fields.len() > 4 {
+        fields[4..]
+            .join(" ")
+            .trim_end_matches(';')
+            .trim_end_matches('"')
+            .trim_start_matches('"')
+            .trim()
+            .to_owned()
+    } else {
+        id.to_string()
+    };
+
+    Ok((
+        Node {
+            owner,
+            name,
+            id,
+            parity,
+        },
+        adj_list,
+    ))
 }
@@ -1,5 +1,14 @@
+//! Parity games, as read from the PGSolver format or built in code with
+//! `ParityGameBuilder`.
+
+use lcsfe_common::LcsfeError;
+use rustc_hash::FxHashSet as HashSet;
+
+/// The nodes of a parity game, each with the identifiers of its successors.
+/// It is built by `parser::parse_pg` or `ParityGameBuilder`, which validate
+/// it.
 #[derive(Debug)]
-pub struct PG(pub Vec<(Node, Vec<usize>)>);
+pub struct PG(pub(crate) Vec<(Node, Vec<usize>)>);
 
 #[derive(Debug)]
 
@@ -10,8 +19,138 @@ pub struct Node {
     pub name: String,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Player {
     Adam,
     Eve,
 }
+
+impl PG {
+    /// Fails if two nodes have the same identifier, if a node has no
+    /// successors, or if a successor is not the identifier of a node.
+    pub fn validate(&self) -> Result<(), LcsfeError> {
+        let mut ids = HashSet::default();
+        if let Some((n, _)) = self.0.iter().find(|(n, _)| !ids.insert(n.id as usize)) {
+            return Err(LcsfeError::Validation(format!(
+                "Invalid parity game: the node {} is declared more than once",
+                n.id
+            )));
+        }
+        for (n, adj_list) in &self.0 {
+            if adj_list.is_empty() {
+                return Err(LcsfeError::Validation(format!(
+                    "Invalid parity game: the node {} has no successors",
+                    n.id
+                )));
+            }
+            if let Some(x) = adj_list.iter().find(|x| !ids.contains(x)) {
+                return Err(LcsfeError::Validation(format!(
+                    "Invalid parity game: the successor {} of node {} is not a node",
+                    x, n.id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builds a parity game in code, e.g. from the states of a simulator, without
+/// writing it to a file first. Nodes are added with `add_node`, and the game
+/// is verified with `ParityGameSpec::from_game`.
+#[derive(Debug, Default)]
+pub struct ParityGameBuilder {
+    nodes: Vec<(Node, Vec<usize>)>,
+}
+
+impl ParityGameBuilder {
+    pub fn new() -> ParityGameBuilder {
+        ParityGameBuilder::default()
+    }
+
+    /// Adds a node, named after its identifier.
+    pub fn add_node(
+        &mut self,
+        id: u32,
+        priority: u32,
+        owner: Player,
+        successors: impl IntoIterator<Item = u32>,
+    ) -> &mut ParityGameBuilder {
+        self.add_named_node(id, priority, owner, successors, id.to_string())
+    }
+
+    /// Adds a node with a name, by which the queries refer to it.
+    pub fn add_named_node(
+        &mut self,
+        id: u32,
+        priority: u32,
+        owner: Player,
+        successors: impl IntoIterator<Item = u32>,
+        name: impl Into<String>,
+    ) -> &mut ParityGameBuilder {
+        self.nodes.push((
+            Node {
+                id,
+                owner,
+                parity: priority,
+                name: name.into(),
+            },
+            successors.into_iter().map(|x| x as usize).collect(),
+        ));
+        self
+    }
+
+    /// The parity game, which is validated as the parsed ones are.
+    pub fn build(self) -> Result<PG, LcsfeError> {
+        let pg = PG(self.nodes);
+        pg.validate()?;
+        Ok(pg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParityGameBuilder, Player};
+    use crate::ParityGameSpec;
+    use lcsfe_common::{InputFlags, SpecOutput};
+
+    /// A game built in code is verified as a parsed one, and it is rejected
+    /// when a successor is missing or a node is declared twice.
+    #[test]
+    fn built_game_is_verified() {
+        let mut builder = ParityGameBuilder::new();
+        builder
+            .add_node(0, 1, Player::Eve, [1, 2])
+            .add_node(1, 2, Player::Adam, [0])
+            .add_named_node(2, 3, Player::Adam, [2], "sink");
+        let spec = ParityGameSpec::from_game(builder.build().unwrap(), None).unwrap();
+        let flags = InputFlags {
+            normalize: false,
+            checkpoint: None,
+        };
+        let pre_proc = spec.pre_proc(&flags).unwrap();
+        for node in ["0", "1", "sink"] {
+            assert_eq!(
+                spec.verify_at(&flags, &pre_proc, node).unwrap().result,
+                spec.verify_with("zielonka", node).unwrap().result,
+                "node {}",
+                node
+            );
+        }
+        assert!(spec
+            .verify_at(&flags, &pre_proc, "0")
+            .unwrap()
+            .verdict
+            .holds());
+
+        let mut builder = ParityGameBuilder::new();
+        builder.add_node(0, 0, Player::Eve, [1]);
+        assert!(builder.build().is_err());
+
+        let mut builder = ParityGameBuilder::new();
+        builder
+            .add_node(0, 0, Player::Eve, [0])
+            .add_node(0, 1, Player::Adam, [0]);
+        let error = builder.build().unwrap_err().to_string();
+        assert!(error.contains("the node 0 is declared more than once"));
+    }
+}
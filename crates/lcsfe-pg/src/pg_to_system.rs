@@ -1,9 +1,14 @@
 use crate::pg::{Player, PG};
 use lcsfe_algorithm::ast::fixpoint_system::{ExpFixEq, FixEq, FixType};
 
+/// The variable of the equation of a node, or of its successor `id`.
+pub fn var_name(id: impl std::fmt::Display) -> String {
+    format!("x_{}", id)
+}
+
 pub fn pg_to_system(pg: &PG, p: Player) -> Vec<FixEq> {
     pg.0.iter().fold(vec![], |mut acc, (n, adj_list)| {
-        let x = format!("x_{}", n.id);
+        let x = var_name(n.id);
 
         let fix_ty = if n.parity % 2 == 0 {
             FixType::Max
@@ -12,9 +17,9 @@ pub fn pg_to_system(pg: &PG, p: Player) -> Vec<FixEq> {
         };
 
         let mut adj_list_iter = adj_list.iter();
-        let first = ExpFixEq::Id(format!("x_{}", adj_list_iter.next().unwrap()));
+        let first = ExpFixEq::Id(var_name(adj_list_iter.next().unwrap()));
         let right_hand = adj_list_iter.fold(first, |acc, elem| {
-            let x_i = ExpFixEq::Id(format!("x_{}", elem));
+            let x_i = ExpFixEq::Id(var_name(elem));
             match acc {
                 id @ ExpFixEq::Id(_) if n.owner == p => ExpFixEq::Or(Box::new(id), Box::new(x_i)),
                 id @ ExpFixEq::Id(_) => ExpFixEq::And(Box::new(id), Box::new(x_i)),
@@ -30,6 +35,7 @@ pub fn pg_to_system(pg: &PG, p: Player) -> Vec<FixEq> {
             var: x,
             fix_ty,
             exp: right_hand,
+            basis: None,
         });
         acc
     })
@@ -0,0 +1,166 @@
+use crate::pg::{Player, PG};
+use lcsfe_algorithm::algorithm::{ParityArena, Player as ArenaPlayer};
+use rustc_hash::FxHashMap as HashMap;
+
+/// Solves the parity game with Zielonka's recursive algorithm, and returns
+/// for each node in `pg`, in the same order, the player who wins from it.
+/// Player `Eve` wins a play whenever the greatest priority which occurs
+/// infinitely often is even.
+pub fn zielonka(pg: &PG) -> Vec<Player> {
+    let index =
+        pg.0.iter()
+            .enumerate()
+            .map(|(i, (n, _))| (n.id as usize, i))
+            .collect::<HashMap<usize, usize>>();
+
+    let arena = ParityArena::new(
+        pg.0.iter()
+            .map(|(n, _)| match n.owner {
+                Player::Eve => ArenaPlayer::Eve,
+                Player::Adam => ArenaPlayer::Adam,
+            })
+            .collect(),
+        pg.0.iter().map(|(n, _)| n.parity as usize).collect(),
+        pg.0.iter()
+            .map(|(_, adj_list)| adj_list.iter().map(|x| index[x]).collect())
+            .collect(),
+    );
+
+    arena
+        .zielonka()
+        .winner
+        .into_iter()
+        .map(|x| match x {
+            ArenaPlayer::Eve => Player::Eve,
+            ArenaPlayer::Adam => Player::Adam,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, io::BufReader};
+
+    use crate::{parser::parse_pg, ParityGameFrontend, ParityGameSpec};
+    use lcsfe_common::{cached_spec, InputFlags, SpecOutput};
+
+    /// Uses Zielonka's algorithm as an oracle for the local algorithm: every
+    /// node of the input games must be assigned the same winner.
+    #[test]
+    fn zielonka_agrees_with_local_algorithm() {
+        for game in ["test_01.gm", "test_02.gm", "test_03.gm"] {
+            let path = format!(
+                "{}/../../tests/parity_games/{}",
+                env!("CARGO_MANIFEST_DIR"),
+                game
+            );
+            let open = || BufReader::new(File::open(&path).unwrap());
+            let nodes = parse_pg(&mut open())
+                .unwrap()
+                .0
+                .iter()
+                .map(|(n, _)| n.name.to_owned())
+                .collect::<Vec<_>>();
+
+            for node in nodes {
+                let spec = ParityGameSpec::new(&mut open(), node.clone()).unwrap();
+                let flags = InputFlags {
+                    normalize: false,
+                    checkpoint: None,
+                };
+                let local = spec.verify(&flags, &spec.pre_proc(&flags).unwrap());
+                assert_eq!(
+                    local.unwrap().result,
+                    spec.zielonka().result,
+                    "{}, node {}",
+                    game,
+                    node
+                );
+            }
+        }
+    }
+
+    /// Verifying all the nodes at once, with the normalized system, must
+    /// agree with Zielonka's algorithm as well, whether it solves the game
+    /// for each node or once for all of them.
+    #[test]
+    fn batch_agrees_with_zielonka() {
+        let path = format!(
+            "{}/../../tests/parity_games/test_02.gm",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let src = std::fs::read_to_string(&path).unwrap();
+        let spec = ParityGameSpec::from_source(&src, "0".into()).unwrap();
+        let flags = InputFlags {
+            normalize: true,
+            checkpoint: None,
+        };
+        let nodes = spec.positions();
+        let results = spec
+            .verify_many(&flags, &spec.pre_proc(&flags).unwrap(), &nodes)
+            .unwrap();
+        let zielonka = spec.verify_many_with("zielonka", &nodes).unwrap();
+        assert_eq!(results.len(), nodes.len());
+        assert_eq!(zielonka.len(), nodes.len());
+        for ((node, result), batch) in nodes.iter().zip(results).zip(zielonka) {
+            assert_eq!(&result.verdict.query.original, node);
+            assert_eq!(&batch.verdict.query.original, node);
+            let single = spec.verify_with("zielonka", node).unwrap();
+            assert_eq!(
+                result.verdict.winner, single.verdict.winner,
+                "node {}",
+                node
+            );
+            assert_eq!(batch.result, single.result, "node {}", node);
+        }
+        assert!(spec.verify_many_with("local", &nodes).is_err());
+    }
+
+    /// The verdicts answered from the cache, without parsing the game, must
+    /// agree with Zielonka's algorithm, and an entry whose digest does not
+    /// match the inputs is computed again.
+    #[test]
+    fn cache_agrees_with_zielonka() {
+        let path = format!(
+            "{}/../../tests/parity_games/test_01.gm",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let path = std::path::Path::new(&path);
+        let dir = std::env::temp_dir().join(format!("lcsfe-cache-{}", std::process::id()));
+        let spec =
+            ParityGameSpec::new(BufReader::new(File::open(path).unwrap()), "0".into()).unwrap();
+        let nodes = spec.positions();
+        let zielonka = spec.verify_many_with("zielonka", &nodes).unwrap();
+
+        for normalize in [false, true] {
+            let flags = InputFlags {
+                normalize,
+                checkpoint: None,
+            };
+            for cached in [false, true] {
+                let (spec, pre_proc) =
+                    cached_spec(&ParityGameFrontend, &[path], None, &flags, &dir).unwrap();
+                assert_eq!(pre_proc.cached, cached);
+                assert_eq!(spec.positions(), nodes);
+                let results = spec.verify_many(&flags, &pre_proc, &nodes).unwrap();
+                for (result, expected) in results.iter().zip(&zielonka) {
+                    assert_eq!(result.result, expected.result, "normalize {}", normalize);
+                }
+            }
+        }
+
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let entry = entry.unwrap().path();
+            let src = std::fs::read_to_string(&entry).unwrap();
+            let digest = entry.file_stem().unwrap().to_str().unwrap()["pg-".len()..].to_owned();
+            std::fs::write(&entry, src.replace(&digest, &"0".repeat(digest.len()))).unwrap();
+        }
+        let flags = InputFlags {
+            normalize: false,
+            checkpoint: None,
+        };
+        let (_, pre_proc) = cached_spec(&ParityGameFrontend, &[path], None, &flags, &dir).unwrap();
+        assert!(!pre_proc.cached);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}